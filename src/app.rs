@@ -1,15 +1,29 @@
-use crate::theme::{Theme, get_themes};
-use sysinfo::{System, RefreshKind, CpuRefreshKind, MemoryRefreshKind, Disks, Networks, ProcessRefreshKind, Components};
-use std::collections::HashMap;
-use ratatui::widgets::TableState;
+use crate::config::Config;
+use crate::cpu::{CpuBreakdown, CpuFreqInfo, CpuGrouping, CpuTimes, CpuTopology};
+use crate::disk::{inode_usage, DiskCounters, DiskRates};
+use crate::filter::ProcessFilter;
+use crate::keymap::{Action, Keymap};
+use crate::procinfo::ProcExtras;
+use crate::theme::Theme;
+use crate::tree::{build_tree, TreeRow};
+use sysinfo::{System, RefreshKind, CpuRefreshKind, MemoryRefreshKind, Disk, Disks, Networks, ProcessRefreshKind, Components, NetworkData, Pid, Process, Signal, Uid, Users};
+use std::cmp::Ordering;
+use std::time::{Duration, Instant};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use ratatui::{layout::Rect, widgets::TableState};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
-#[allow(dead_code)]
+#[derive(Clone, Copy, PartialEq)]
 pub enum InputMode {
     Normal,
     Editing,
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+#[value(rename_all = "snake_case")]
 pub enum LayoutMode {
     Dashboard, // The current default split view
     Vertical,  // Everything stacked vertically
@@ -26,42 +40,330 @@ impl LayoutMode {
     }
 }
 
+/// How per-core usage is drawn in the CPU panel. Bars and sparklines fall
+/// back to the heatmap when the panel is too small to fit every core.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CpuView {
+    Bars,
+    Sparklines,
+    Heatmap,
+}
+
+impl CpuView {
+    pub fn next(&self) -> Self {
+        match self {
+            CpuView::Bars => CpuView::Sparklines,
+            CpuView::Sparklines => CpuView::Heatmap,
+            CpuView::Heatmap => CpuView::Bars,
+        }
+    }
+}
+
+/// Panels that can be shown alongside the process table.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Panel {
+    Cpu,
+    Memory,
+    Storage,
+    Network,
+    Sensors,
+}
+
+/// Column the process table is ordered by.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortColumn {
+    Pid,
+    Name,
+    Cpu,
+    Memory,
+    User,
+    StartTime,
+    RunTime,
+    DiskRead,
+    DiskWrite,
+    Threads,
+}
+
+impl SortColumn {
+    /// All columns in the order they appear in the process table.
+    pub const ALL: [SortColumn; 10] = [
+        SortColumn::Pid,
+        SortColumn::Name,
+        SortColumn::Cpu,
+        SortColumn::Memory,
+        SortColumn::User,
+        SortColumn::StartTime,
+        SortColumn::RunTime,
+        SortColumn::DiskRead,
+        SortColumn::DiskWrite,
+        SortColumn::Threads,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            SortColumn::Pid => "PID",
+            SortColumn::Name => "Name",
+            SortColumn::Cpu => "CPU%",
+            SortColumn::Memory => "Mem",
+            SortColumn::User => "User",
+            SortColumn::StartTime => "Start",
+            SortColumn::RunTime => "Time",
+            SortColumn::DiskRead => "Read/s",
+            SortColumn::DiskWrite => "Write/s",
+            SortColumn::Threads => "Thr",
+        }
+    }
+
+    /// Ascending comparison of two processes on this column.
+    pub fn compare(&self, a: &Process, b: &Process, users: &Users) -> Ordering {
+        match self {
+            SortColumn::Pid => a.pid().cmp(&b.pid()),
+            SortColumn::Name => a.name().cmp(b.name()),
+            SortColumn::Cpu => a.cpu_usage().partial_cmp(&b.cpu_usage()).unwrap_or(Ordering::Equal),
+            SortColumn::Memory => a.memory().cmp(&b.memory()),
+            SortColumn::User => compare_users(users, a.user_id(), b.user_id()),
+            SortColumn::StartTime => a.start_time().cmp(&b.start_time()),
+            SortColumn::RunTime => a.run_time().cmp(&b.run_time()),
+            SortColumn::DiskRead => a.disk_usage().read_bytes.cmp(&b.disk_usage().read_bytes),
+            SortColumn::DiskWrite => a.disk_usage().written_bytes.cmp(&b.disk_usage().written_bytes),
+            SortColumn::Threads => thread_count(a).cmp(&thread_count(b)),
+        }
+    }
+}
+
+/// Login name of `uid`, or the numeric UID when it has no account.
+/// Orders by user name. Accounts without a name follow the named ones in
+/// numeric UID order (so `999` comes before `1000`), then processes
+/// without a user.
+fn compare_users(users: &Users, a: Option<&Uid>, b: Option<&Uid>) -> Ordering {
+    let name = |uid: Option<&Uid>| uid.and_then(|uid| users.get_user_by_id(uid)).map(|user| user.name());
+    match (name(a), name(b)) {
+        (Some(x), Some(y)) => x.cmp(y),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.is_none().cmp(&b.is_none()).then_with(|| a.cmp(&b)),
+    }
+}
+
+pub fn user_name(users: &Users, uid: Option<&Uid>) -> String {
+    match uid {
+        Some(uid) => users
+            .get_user_by_id(uid)
+            .map(|u| u.name().to_string())
+            .unwrap_or_else(|| uid.to_string()),
+        None => "?".to_string(),
+    }
+}
+
+/// Real user of a process, followed by the effective user when it differs
+/// (e.g. `alice→root` for a setuid program).
+pub fn process_user(users: &Users, process: &Process) -> String {
+    let real = user_name(users, process.user_id());
+    match process.effective_user_id() {
+        Some(effective) if Some(effective) != process.user_id() => {
+            format!("{}→{}", real, user_name(users, Some(effective)))
+        }
+        _ => real,
+    }
+}
+
+/// Processes, CPU and memory owned by one user.
+pub struct UserSummary {
+    pub name: String,
+    pub processes: usize,
+    pub cpu: f32,
+    pub memory: u64,
+}
+
+/// Number of threads of a process, where the platform reports them.
+pub fn thread_count(process: &Process) -> Option<usize> {
+    process.tasks().map(|tasks| tasks.len())
+}
+
+/// Per-interface network rates and history, in bytes per second.
+#[derive(Default)]
+pub struct InterfaceStats {
+    pub rx_rate: f64,
+    pub tx_rate: f64,
+    pub rx_peak: f64,
+    pub tx_peak: f64,
+    pub rx_history: Vec<(f64, f64)>,
+    pub tx_history: Vec<(f64, f64)>,
+}
+
+/// Fraction of a mount's space in use, 0 when the size is unknown.
+pub fn disk_usage(disk: &Disk) -> f64 {
+    let total = disk.total_space();
+    if total == 0 {
+        return 0.0;
+    }
+    total.saturating_sub(disk.available_space()) as f64 / total as f64
+}
+
+/// Loopback, container bridges, VPN tunnels and other software interfaces.
+pub fn is_virtual_interface(name: &str, data: &NetworkData) -> bool {
+    const PREFIXES: [&str; 22] = [
+        "lo", "docker", "br-", "veth", "virbr", "vnet", "tun", "tap", "wg", "vmnet", "vboxnet",
+        "utun", "awdl", "llw", "cni", "flannel", "cali", "tailscale", "ifb", "dummy", "sit", "gre",
+    ];
+    PREFIXES.iter().any(|p| name.starts_with(p)) || data.mac_address().is_unspecified()
+}
+
+/// Signals offered by the process signal picker, in display order.
+pub const SIGNALS: [(Signal, &str); 8] = [
+    (Signal::Term, "SIGTERM"),
+    (Signal::Kill, "SIGKILL"),
+    (Signal::Stop, "SIGSTOP"),
+    (Signal::Continue, "SIGCONT"),
+    (Signal::Hangup, "SIGHUP"),
+    (Signal::Interrupt, "SIGINT"),
+    (Signal::User1, "SIGUSR1"),
+    (Signal::User2, "SIGUSR2"),
+];
+
+const STATUS_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Where clickable parts were drawn in the last frame, so mouse events
+/// can be mapped back to what is under the pointer.
+#[derive(Default)]
+pub struct ClickAreas {
+    /// Key hints in the header and the action each one stands for.
+    pub hints: Vec<(Rect, Action)>,
+    pub panels: Vec<(Panel, Rect)>,
+    /// Whole process panel, including its border.
+    pub processes: Rect,
+    /// Process table column headers.
+    pub columns: Vec<(SortColumn, Rect)>,
+    /// Process rows below the header; the first one shows the row at the
+    /// table offset.
+    pub process_rows: Rect,
+}
+
+/// State of the signal picker popup opened on the selected process.
+pub struct SignalDialog {
+    pub pid: Pid,
+    pub name: String,
+    pub selected: usize,
+    pub confirming: bool,
+}
+
+/// State of the detail popup opened on a process with Enter.
+pub struct ProcessDetails {
+    pub pid: Pid,
+    pub scroll: usize,
+    pub extras: ProcExtras,
+}
+
+/// CPU% and resident memory (bytes) of the selected process, recorded from
+/// the tick it was selected.
+pub struct ProcessHistory {
+    pub pid: Pid,
+    pub cpu: Vec<(f64, f64)>,
+    pub memory: Vec<(f64, f64)>,
+}
+
 pub struct App {
+    pub config: Config,
+    pub config_path: Option<PathBuf>,
+    pub tick_rate: Duration,
     pub sys: System,
     pub disks: Disks,
     pub networks: Networks,
     pub components: Components,
+    pub users: Users,
     pub themes: HashMap<String, Theme>,
+    pub keymap: Keymap,
     pub current_theme: Theme,
     pub should_quit: bool,
     pub theme_list: Vec<String>,
     pub selected_theme_index: usize,
-    #[allow(dead_code)]
     pub show_help: bool,
+    pub help_scroll: usize,
     pub layout_mode: LayoutMode,
+    /// Set by t/T and l, so only runtime changes are saved.
+    theme_changed: bool,
+    layout_changed: bool,
+    pub sort_column: SortColumn,
+    pub sort_descending: bool,
+    pub input_mode: InputMode,
+    pub filter: ProcessFilter,
+    pub filter_match_count: usize,
+    /// Restricts the process table to these PIDs when non-empty (`--pid`).
+    pub watched_pids: HashSet<Pid>,
+    /// Restricts the process table to one owner (`--user`).
+    pub user_filter: Option<Uid>,
+    pub tree_mode: bool,
+    pub collapsed_pids: HashSet<Pid>,
+    pub tree_rows: HashMap<Pid, TreeRow>,
     
     // History Data for Charts
     pub cpu_history: Vec<Vec<(f64, f64)>>,
+    pub cpu_total_history: Vec<(f64, f64)>,
+    cpu_times: Option<CpuTimes>,
+    pub cpu_breakdown: Option<CpuBreakdown>,
+    pub cpu_view: CpuView,
+    pub cpu_topology: CpuTopology,
+    pub cpu_grouping: CpuGrouping,
+    /// Average current frequency in MHz.
+    pub cpu_freq_history: Vec<(f64, f64)>,
+    /// Per-core frequency limits and governors, read while the details popup is open.
+    pub cpu_freq_info: Vec<CpuFreqInfo>,
+    pub show_cpu_details: bool,
+    pub cpu_details_scroll: usize,
+    /// Temperature history per component, indexed like `components`.
+    pub sensor_history: Vec<Vec<(f64, f64)>>,
     pub mem_history: Vec<(f64, f64)>,
     #[allow(dead_code)]
     pub swap_history: Vec<(f64, f64)>,
     pub net_rx_history: Vec<(f64, f64)>,
     pub net_tx_history: Vec<(f64, f64)>,
     pub tick_count: f64,
+    last_refresh: Instant,
+    /// Seconds between the last two refreshes, for per-process I/O rates.
+    pub refresh_elapsed: f64,
+    pub net_rx_rate: f64,
+    pub net_tx_rate: f64,
+    pub net_rx_peak: f64,
+    pub net_tx_peak: f64,
+    pub interfaces: BTreeMap<String, InterfaceStats>,
+    /// Interface driving the network summary and chart; `None` means all.
+    pub selected_interface: Option<String>,
+    pub show_interface_list: bool,
+    disk_counters: Option<BTreeMap<String, DiskCounters>>,
+    /// Current activity per block device; the history is only kept for the totals.
+    pub disk_io: BTreeMap<String, DiskRates>,
+    pub disk_read_history: Vec<(f64, f64)>,
+    pub disk_write_history: Vec<(f64, f64)>,
+    /// Per-device I/O table and chart instead of capacity gauges.
+    pub show_disk_io: bool,
+    /// Used and total inodes per mount point, refreshed every tick.
+    pub inodes: HashMap<PathBuf, (u64, u64)>,
+    pub storage_scroll: usize,
     
     // UI States
-    #[allow(dead_code)]
     pub process_table_state: TableState,
+    pub process_list: Vec<Pid>,
+    pub selected_pid: Option<Pid>,
+    pub process_page_size: usize,
+    pub signal_dialog: Option<SignalDialog>,
+    pub process_details: Option<ProcessDetails>,
+    pub process_history: Option<ProcessHistory>,
+    /// Per-user totals instead of the process table.
+    pub show_user_summary: bool,
+    /// Panel shown alone below the header, toggled by clicking it.
+    pub maximized_panel: Option<Panel>,
+    pub click_areas: ClickAreas,
+    status_message: Option<(String, Instant)>,
 }
 
 impl App {
-    pub fn new() -> App {
-        let themes = get_themes();
+    pub fn new(config: Config, config_path: Option<PathBuf>, themes: HashMap<String, Theme>) -> App {
         let mut theme_list: Vec<String> = themes.keys().cloned().collect();
         theme_list.sort();
         
         let default_theme_name = "x";
-        let current_theme = themes.get(default_theme_name).cloned().unwrap_or_else(|| {
+        let current_theme = themes.get(&config.theme).or_else(|| themes.get(default_theme_name)).cloned().unwrap_or_else(|| {
              themes.values().next().unwrap().clone()
         });
 
@@ -76,30 +378,104 @@ impl App {
         let disks = Disks::new_with_refreshed_list();
         let networks = Networks::new_with_refreshed_list();
         let components = Components::new_with_refreshed_list();
+        let users = Users::new_with_refreshed_list();
+        let cpu_topology = CpuTopology::read(sys.cpus().len());
 
         let mut process_table_state = TableState::default();
         process_table_state.select(Some(0));
 
-        App {
+        let unknown_theme = !themes.contains_key(&config.theme);
+        let tick_rate = Duration::from_millis(config.tick_rate_ms);
+        let layout_mode = config.layout;
+        let sort_column = config.processes.sort;
+        let sort_descending = config.processes.sort_descending;
+        let tree_mode = config.processes.tree;
+        let (keymap, key_conflicts) = Keymap::new(&config.keys);
+
+        let mut app = App {
+            config,
+            config_path,
+            tick_rate,
             sys,
             disks,
             networks,
             components,
+            users,
             themes,
+            keymap,
             current_theme,
             should_quit: false,
             theme_list,
             selected_theme_index,
             show_help: false,
-            layout_mode: LayoutMode::Dashboard,
+            help_scroll: 0,
+            layout_mode,
+            theme_changed: false,
+            layout_changed: false,
+            sort_column,
+            sort_descending,
+            input_mode: InputMode::Normal,
+            filter: ProcessFilter::new(),
+            filter_match_count: 0,
+            watched_pids: HashSet::new(),
+            user_filter: None,
+            tree_mode,
+            collapsed_pids: HashSet::new(),
+            tree_rows: HashMap::new(),
             cpu_history: vec![],
+            cpu_total_history: vec![],
+            cpu_times: CpuTimes::read(),
+            cpu_breakdown: None,
+            cpu_view: CpuView::Bars,
+            cpu_topology,
+            cpu_grouping: CpuGrouping::None,
+            cpu_freq_history: vec![],
+            cpu_freq_info: vec![],
+            show_cpu_details: false,
+            cpu_details_scroll: 0,
+            sensor_history: vec![],
             mem_history: vec![],
             swap_history: vec![],
             net_rx_history: vec![],
             net_tx_history: vec![],
             tick_count: 0.0,
+            last_refresh: Instant::now(),
+            refresh_elapsed: tick_rate.as_secs_f64(),
+            net_rx_rate: 0.0,
+            net_tx_rate: 0.0,
+            net_rx_peak: 0.0,
+            net_tx_peak: 0.0,
+            interfaces: BTreeMap::new(),
+            selected_interface: None,
+            show_interface_list: false,
+            disk_counters: DiskCounters::read_all(),
+            disk_io: BTreeMap::new(),
+            disk_read_history: vec![],
+            disk_write_history: vec![],
+            show_disk_io: false,
+            inodes: HashMap::new(),
+            storage_scroll: 0,
             process_table_state,
+            process_list: vec![],
+            selected_pid: None,
+            process_page_size: 10,
+            signal_dialog: None,
+            process_details: None,
+            process_history: None,
+            show_user_summary: false,
+            maximized_panel: None,
+            click_areas: ClickAreas::default(),
+            status_message: None,
+        };
+        app.selected_interface = app.config.network.interface.clone();
+        if unknown_theme {
+            app.append_status(format!("Unknown theme '{}', using '{}'", app.config.theme, app.current_theme.name));
         }
+        if !key_conflicts.is_empty() {
+            app.append_status(format!("Conflicting key bindings: {}", key_conflicts.join("; ")));
+        }
+        app.refresh_process_list();
+        app
     }
 
     pub fn on_tick(&mut self) {
@@ -107,45 +483,660 @@ impl App {
         self.disks.refresh(true);
         self.networks.refresh(true);
         self.components.refresh(true);
+        // Accounts rarely change; re-read them when a process has an unknown owner
+        let users = &self.users;
+        let unknown_owner = |p: &Process| p.user_id().is_some_and(|uid| users.get_user_by_id(uid).is_none());
+        if (self.tick_count as u64).is_multiple_of(30) && self.sys.processes().values().any(unknown_owner) {
+            self.users.refresh();
+        }
         
         self.tick_count += 1.0;
         let x = self.tick_count;
 
         // Update CPU History
+        self.cpu_total_history.push((x, self.sys.global_cpu_usage() as f64));
+        if self.cpu_total_history.len() > self.config.history_length {
+            self.cpu_total_history.remove(0);
+        }
+        if let Some(times) = CpuTimes::read() {
+            if let Some(previous) = &self.cpu_times {
+                self.cpu_breakdown = Some(times.breakdown_since(previous));
+            }
+            self.cpu_times = Some(times);
+        }
+
         let cpus = self.sys.cpus();
         if self.cpu_history.len() != cpus.len() {
             self.cpu_history = vec![vec![]; cpus.len()];
         }
         for (i, cpu) in cpus.iter().enumerate() {
             self.cpu_history[i].push((x, cpu.cpu_usage() as f64));
-            if self.cpu_history[i].len() > 100 {
+            if self.cpu_history[i].len() > self.config.history_length {
                 self.cpu_history[i].remove(0);
             }
         }
+        // sysinfo reads scaling_cur_freq from /sys/devices/system/cpu on Linux
+        if !cpus.is_empty() {
+            let average = cpus.iter().map(|c| c.frequency() as f64).sum::<f64>() / cpus.len() as f64;
+            self.cpu_freq_history.push((x, average));
+            if self.cpu_freq_history.len() > self.config.history_length {
+                self.cpu_freq_history.remove(0);
+            }
+        }
+        if self.show_cpu_details {
+            self.cpu_freq_info = (0..cpus.len()).map(CpuFreqInfo::read).collect();
+        }
+
+        if self.sensor_history.len() != self.components.len() {
+            self.sensor_history = vec![vec![]; self.components.len()];
+        }
+        for (i, component) in self.components.iter().enumerate() {
+            let Some(temp) = component.temperature().filter(|t| t.is_finite()) else { continue };
+            self.sensor_history[i].push((x, temp as f64));
+            if self.sensor_history[i].len() > self.config.history_length {
+                self.sensor_history[i].remove(0);
+            }
+        }
 
         // Update Memory History
         let total_mem = self.sys.total_memory() as f64;
         let used_mem = self.sys.used_memory() as f64;
         let mem_usage = if total_mem > 0.0 { used_mem / total_mem * 100.0 } else { 0.0 };
         self.mem_history.push((x, mem_usage));
-        if self.mem_history.len() > 100 {
+        if self.mem_history.len() > self.config.history_length {
             self.mem_history.remove(0);
         }
 
-        // Update Net History
+        // Update Net History (bytes per second over the actual refresh interval)
+        let elapsed = self.last_refresh.elapsed().as_secs_f64().max(0.001);
+        self.last_refresh = Instant::now();
+        self.refresh_elapsed = elapsed;
         let mut total_rx = 0;
         let mut total_tx = 0;
-        for (_, network) in &self.networks {
-            total_rx += network.received();
-            total_tx += network.transmitted();
+        let history_length = self.config.history_length;
+        for (name, network) in &self.networks {
+            let stats = self.interfaces.entry(name.clone()).or_default();
+            stats.rx_rate = network.received() as f64 / elapsed;
+            stats.tx_rate = network.transmitted() as f64 / elapsed;
+            stats.rx_peak = stats.rx_peak.max(stats.rx_rate);
+            stats.tx_peak = stats.tx_peak.max(stats.tx_rate);
+            stats.rx_history.push((x, stats.rx_rate));
+            stats.tx_history.push((x, stats.tx_rate));
+            if stats.rx_history.len() > history_length {
+                stats.rx_history.remove(0);
+                stats.tx_history.remove(0);
+            }
+            if !(self.config.network.hide_virtual && is_virtual_interface(name, network)) {
+                total_rx += network.received();
+                total_tx += network.transmitted();
+            }
         }
-        
-        self.net_rx_history.push((x, total_rx as f64)); 
-        self.net_tx_history.push((x, total_tx as f64));
-         if self.net_rx_history.len() > 100 {
+        let networks = &self.networks;
+        self.interfaces.retain(|name, _| networks.contains_key(name));
+        if self.selected_interface.as_ref().is_some_and(|name| !self.interfaces.contains_key(name)) {
+            self.selected_interface = None;
+        }
+
+        self.net_rx_rate = total_rx as f64 / elapsed;
+        self.net_tx_rate = total_tx as f64 / elapsed;
+        self.net_rx_peak = self.net_rx_peak.max(self.net_rx_rate);
+        self.net_tx_peak = self.net_tx_peak.max(self.net_tx_rate);
+
+        self.net_rx_history.push((x, self.net_rx_rate));
+        self.net_tx_history.push((x, self.net_tx_rate));
+         if self.net_rx_history.len() > self.config.history_length {
             self.net_rx_history.remove(0);
             self.net_tx_history.remove(0);
         }
+
+        self.update_disk_io(elapsed);
+        self.inodes = self.disks.list().iter()
+            .filter_map(|d| Some((d.mount_point().to_path_buf(), inode_usage(d.mount_point())?)))
+            .collect();
+
+        if let Some(details) = &mut self.process_details {
+            details.extras = ProcExtras::read(details.pid);
+        }
+
+        self.refresh_process_list();
+        self.record_process_history();
+    }
+
+    fn record_process_history(&mut self) {
+        let tracked = self.process_details.as_ref().map(|d| d.pid).or(self.selected_pid);
+        let Some(process) = tracked.and_then(|pid| self.sys.process(pid)) else {
+            self.process_history = None;
+            return;
+        };
+        let history = match &mut self.process_history {
+            Some(history) if history.pid == process.pid() => history,
+            _ => self.process_history.insert(ProcessHistory { pid: process.pid(), cpu: vec![], memory: vec![] }),
+        };
+        let x = self.tick_count;
+        history.cpu.push((x, process.cpu_usage() as f64));
+        history.memory.push((x, process.memory() as f64));
+        if history.cpu.len() > self.config.history_length {
+            history.cpu.remove(0);
+            history.memory.remove(0);
+        }
+    }
+
+    /// Updates per-device disk rates from `/proc/diskstats`, or from
+    /// sysinfo's per-mount counters where that is not available.
+    fn update_disk_io(&mut self, elapsed: f64) {
+        let x = self.tick_count;
+        let history_length = self.config.history_length;
+        let mut rates: BTreeMap<String, DiskRates> = BTreeMap::new();
+        match DiskCounters::read_all() {
+            Some(counters) => {
+                if let Some(previous) = &self.disk_counters {
+                    for (name, now) in &counters {
+                        let before = previous.get(name).unwrap_or(now);
+                        rates.insert(name.clone(), now.rates_since(before, elapsed));
+                    }
+                }
+                self.disk_counters = Some(counters);
+            }
+            None => {
+                // A device mounted in several places reports the same counters for each
+                for disk in self.disks.list() {
+                    let usage = disk.usage();
+                    let entry = rates.entry(disk.name().to_string_lossy().into_owned()).or_default();
+                    entry.read_bytes = entry.read_bytes.max(usage.read_bytes as f64 / elapsed);
+                    entry.write_bytes = entry.write_bytes.max(usage.written_bytes as f64 / elapsed);
+                }
+            }
+        }
+
+        let total_read = rates.values().map(|r| r.read_bytes).sum();
+        let total_write = rates.values().map(|r| r.write_bytes).sum();
+        self.disk_io = rates;
+
+        self.disk_read_history.push((x, total_read));
+        self.disk_write_history.push((x, total_write));
+        if self.disk_read_history.len() > history_length {
+            self.disk_read_history.remove(0);
+            self.disk_write_history.remove(0);
+        }
+    }
+
+    /// Rebuilds the ordered PID list shown in the process table and keeps
+    /// the selection on the same PID even when the sort order changes.
+    pub fn refresh_process_list(&mut self) {
+        let filter = &self.filter;
+        let users = &self.users;
+        let watched = &self.watched_pids;
+        let user = self.user_filter.as_ref();
+        let processes = self.sys.processes();
+        let mut procs: Vec<&Process> = processes
+            .values()
+            .filter(|p| watched.is_empty() || watched.contains(&p.pid()))
+            .filter(|p| user.is_none() || p.user_id() == user)
+            .filter(|p| filter.matches(p, users))
+            .collect();
+        self.filter_match_count = procs.len();
+
+        let column = self.sort_column;
+        let descending = self.sort_descending;
+        let compare = |a: &Process, b: &Process| {
+            let order = column.compare(a, b, users).then_with(|| a.pid().cmp(&b.pid()));
+            if descending { order.reverse() } else { order }
+        };
+
+        if self.tree_mode {
+            // Keep the ancestors of every match so filtered processes stay in context
+            let mut visible: HashSet<Pid> = HashSet::new();
+            for p in &procs {
+                let mut current = Some(p.pid());
+                while let Some(pid) = current {
+                    if !visible.insert(pid) {
+                        break;
+                    }
+                    current = processes.get(&pid).and_then(|p| p.parent());
+                }
+            }
+            self.collapsed_pids.retain(|pid| processes.contains_key(pid));
+            let (order, rows) = build_tree(processes, &visible, &self.collapsed_pids, compare);
+            self.process_list = order;
+            self.tree_rows = rows;
+        } else {
+            procs.sort_by(|a, b| compare(a, b));
+            self.process_list = procs.iter().map(|p| p.pid()).collect();
+            self.tree_rows.clear();
+        }
+
+        if self.process_list.is_empty() {
+            self.selected_pid = None;
+            self.process_table_state.select(None);
+            return;
+        }
+
+        let index = self
+            .selected_pid
+            .and_then(|pid| self.process_list.iter().position(|p| *p == pid))
+            .unwrap_or_else(|| {
+                // The selected process exited (or nothing was selected yet):
+                // stay on the same row instead of jumping back to the top.
+                self.process_table_state.selected().unwrap_or(0).min(self.process_list.len() - 1)
+            });
+        self.select_process_index(index);
+    }
+
+    fn select_process_index(&mut self, index: usize) {
+        if self.process_list.is_empty() {
+            return;
+        }
+        let index = index.min(self.process_list.len() - 1);
+        self.selected_pid = Some(self.process_list[index]);
+        self.process_table_state.select(Some(index));
+    }
+
+    pub fn move_process_selection(&mut self, delta: isize) {
+        let current = self.process_table_state.selected().unwrap_or(0);
+        let index = current.saturating_add_signed(delta);
+        self.select_process_index(index);
+    }
+
+    /// Selects the process drawn on `row` of the visible table rows.
+    pub fn select_process_row(&mut self, row: usize) {
+        let index = self.process_table_state.offset() + row;
+        if index < self.process_list.len() {
+            self.select_process_index(index);
+        }
+    }
+
+    pub fn select_next_process(&mut self) {
+        self.move_process_selection(1);
+    }
+
+    pub fn select_previous_process(&mut self) {
+        self.move_process_selection(-1);
+    }
+
+    pub fn page_down_processes(&mut self) {
+        self.move_process_selection(self.process_page_size.max(1) as isize);
+    }
+
+    pub fn page_up_processes(&mut self) {
+        self.move_process_selection(-(self.process_page_size.max(1) as isize));
+    }
+
+    pub fn select_first_process(&mut self) {
+        self.select_process_index(0);
+    }
+
+    pub fn select_last_process(&mut self) {
+        self.select_process_index(self.process_list.len().saturating_sub(1));
+    }
+
+    pub fn start_filter(&mut self) {
+        self.input_mode = InputMode::Editing;
+    }
+
+    /// Leaves the prompt but keeps the filter applied.
+    pub fn finish_filter(&mut self) {
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn clear_filter(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.filter.clear();
+        self.refresh_process_list();
+    }
+
+    pub fn filter_push(&mut self, c: char) {
+        self.filter.push(c);
+        self.refresh_process_list();
+    }
+
+    pub fn filter_pop(&mut self) {
+        self.filter.pop();
+        self.refresh_process_list();
+    }
+
+    pub fn toggle_filter_regex(&mut self) {
+        self.filter.toggle_regex();
+        self.refresh_process_list();
+    }
+
+    pub fn toggle_filter_case(&mut self) {
+        self.filter.toggle_case();
+        self.refresh_process_list();
+    }
+
+    /// Moves the selection to the next row matching the filter, wrapping around.
+    /// Only the tree view keeps non-matching rows (the ancestors of matches);
+    /// the flat list holds nothing but matches, so this is a wrapping step.
+    pub fn select_next_match(&mut self) {
+        self.jump_to_match(true);
+    }
+
+    pub fn select_previous_match(&mut self) {
+        self.jump_to_match(false);
+    }
+
+    fn jump_to_match(&mut self, forward: bool) {
+        if !self.filter.is_active() || self.process_list.is_empty() {
+            return;
+        }
+        let len = self.process_list.len();
+        let current = self.process_table_state.selected().unwrap_or(0);
+        for step in 1..=len {
+            let index = if forward { (current + step) % len } else { (current + len - step) % len };
+            let pid = self.process_list[index];
+            if self.sys.process(pid).is_some_and(|p| self.filter.matches(p, &self.users)) {
+                self.select_process_index(index);
+                return;
+            }
+        }
+    }
+
+    /// Names of the interfaces shown in the network panel, in display order.
+    pub fn visible_interfaces(&self) -> Vec<String> {
+        self.interfaces
+            .keys()
+            .filter(|name| {
+                !self.config.network.hide_virtual
+                    || self.networks.get(*name).is_some_and(|data| !is_virtual_interface(name, data))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Cycles the chart through "all interfaces" and each visible interface.
+    pub fn next_interface(&mut self) {
+        let names = self.visible_interfaces();
+        let next = match &self.selected_interface {
+            None => 0,
+            Some(current) => names.iter().position(|n| n == current).map_or(0, |i| i + 1),
+        };
+        self.selected_interface = names.get(next).cloned();
+    }
+
+    pub fn toggle_interface_list(&mut self) {
+        self.show_interface_list = !self.show_interface_list;
+    }
+
+    pub fn toggle_virtual_interfaces(&mut self) {
+        self.config.network.hide_virtual = !self.config.network.hide_virtual;
+        let visible = self.visible_interfaces();
+        if self.selected_interface.as_ref().is_some_and(|name| !visible.contains(name)) {
+            self.selected_interface = None;
+        }
+    }
+
+    pub fn next_cpu_view(&mut self) {
+        self.cpu_view = self.cpu_view.next();
+    }
+
+    pub fn next_cpu_grouping(&mut self) {
+        self.cpu_grouping = self.cpu_grouping.next();
+        let count = self.sys.cpus().len();
+        let groups = self.cpu_topology.groups(count, self.cpu_grouping);
+        let labelled = groups.first().is_some_and(|(label, _)| !label.is_empty());
+        if self.cpu_grouping != CpuGrouping::None && !labelled {
+            self.set_status(format!("CPU grouping: {} (not reported by this system)", self.cpu_grouping.label()));
+        } else {
+            self.set_status(format!("CPU grouping: {}", self.cpu_grouping.label()));
+        }
+    }
+
+    pub fn toggle_tree_mode(&mut self) {
+        self.tree_mode = !self.tree_mode;
+        self.refresh_process_list();
+    }
+
+    /// Collapses the selected subtree, or moves to the parent when it is
+    /// already collapsed or has no children.
+    pub fn collapse_selected(&mut self) {
+        let Some(pid) = self.selected_pid.filter(|_| self.tree_mode) else { return };
+        let expandable = self.tree_rows.get(&pid).is_some_and(|row| row.has_children && !row.collapsed);
+        if expandable {
+            self.collapsed_pids.insert(pid);
+        } else if let Some(parent) = self.sys.process(pid).and_then(|p| p.parent()) {
+            if self.process_list.contains(&parent) {
+                self.selected_pid = Some(parent);
+            }
+        }
+        self.refresh_process_list();
+    }
+
+    pub fn expand_selected(&mut self) {
+        let Some(pid) = self.selected_pid.filter(|_| self.tree_mode) else { return };
+        if self.collapsed_pids.remove(&pid) {
+            self.refresh_process_list();
+        }
+    }
+
+    pub fn toggle_collapse_selected(&mut self) {
+        let Some(pid) = self.selected_pid.filter(|_| self.tree_mode) else { return };
+        if !self.collapsed_pids.remove(&pid) && self.tree_rows.get(&pid).is_some_and(|row| row.has_children) {
+            self.collapsed_pids.insert(pid);
+        }
+        self.refresh_process_list();
+    }
+
+    pub fn next_sort_column(&mut self) {
+        self.cycle_sort_column(1);
+    }
+
+    pub fn previous_sort_column(&mut self) {
+        self.cycle_sort_column(-1);
+    }
+
+    /// Steps through the columns currently shown in the process table.
+    fn cycle_sort_column(&mut self, step: isize) {
+        let columns = &self.config.processes.columns;
+        let len = columns.len() as isize;
+        let current = columns.iter().position(|c| *c == self.sort_column).map(|i| i as isize).unwrap_or(if step > 0 { -1 } else { 0 });
+        self.sort_column = columns[(current + step).rem_euclid(len) as usize];
+        self.refresh_process_list();
+    }
+
+    /// Sorts by `column`, or reverses the order if it already is the sort column.
+    pub fn sort_by(&mut self, column: SortColumn) {
+        if self.sort_column == column {
+            self.sort_descending = !self.sort_descending;
+        } else {
+            self.sort_column = column;
+        }
+        self.refresh_process_list();
+    }
+
+    pub fn toggle_sort_direction(&mut self) {
+        self.sort_descending = !self.sort_descending;
+        self.refresh_process_list();
+    }
+
+    pub fn open_process_details(&mut self) {
+        let Some(pid) = self.selected_pid else { return };
+        self.process_details = Some(ProcessDetails { pid, scroll: 0, extras: ProcExtras::read(pid) });
+    }
+
+    pub fn close_process_details(&mut self) {
+        self.process_details = None;
+    }
+
+    /// Scrolls the detail popup; the renderer clamps it to the content.
+    pub fn scroll_process_details(&mut self, delta: isize) {
+        if let Some(details) = &mut self.process_details {
+            details.scroll = details.scroll.saturating_add_signed(delta);
+        }
+    }
+
+    pub fn open_signal_dialog(&mut self) {
+        if let Some(pid) = self.selected_pid {
+            self.open_signal_dialog_for(pid);
+        }
+    }
+
+    /// Opens the signal picker for `pid`, unless that process has exited.
+    pub fn open_signal_dialog_for(&mut self, pid: Pid) {
+        let Some(process) = self.sys.process(pid) else {
+            self.set_status(format!("Process {} has exited", pid));
+            return;
+        };
+        self.signal_dialog = Some(SignalDialog {
+            pid,
+            name: process.name().to_string_lossy().into_owned(),
+            selected: 0,
+            confirming: false,
+        });
+    }
+
+    /// Sensors at or above their critical temperature, with the reading.
+    pub fn critical_sensors(&self) -> Vec<(&str, f32)> {
+        self.components
+            .iter()
+            .filter_map(|c| {
+                let temp = c.temperature()?;
+                let critical = c.critical().filter(|c| *c > 0.0)?;
+                (temp >= critical).then_some((c.label(), temp))
+            })
+            .collect()
+    }
+
+    /// Mounts shown in the storage panel, without hidden filesystem types
+    /// and mount points, in mount point or usage order.
+    pub fn visible_disks(&self) -> Vec<&Disk> {
+        let storage = &self.config.storage;
+        let mut disks: Vec<&Disk> = self.disks.list().iter()
+            .filter(|d| {
+                let fs = d.file_system().to_string_lossy();
+                !storage.hide_filesystems.iter().any(|hidden| hidden.eq_ignore_ascii_case(&fs))
+            })
+            .filter(|d| !storage.hide_mounts.iter().any(|prefix| d.mount_point().starts_with(Path::new(prefix))))
+            .collect();
+        if storage.sort_by_usage {
+            disks.sort_by(|a, b| disk_usage(b).total_cmp(&disk_usage(a)).then_with(|| a.mount_point().cmp(b.mount_point())));
+        } else {
+            disks.sort_by(|a, b| a.mount_point().cmp(b.mount_point()));
+        }
+        disks
+    }
+
+    /// Scrolls the storage list; the renderer clamps it to the mounts that
+    /// do not fit.
+    pub fn scroll_storage(&mut self, delta: isize) {
+        self.storage_scroll = self.storage_scroll.saturating_add_signed(delta);
+    }
+
+    pub fn toggle_storage_sort(&mut self) {
+        self.config.storage.sort_by_usage = !self.config.storage.sort_by_usage;
+        self.storage_scroll = 0;
+    }
+
+    /// Totals per real user over the processes the table would show,
+    /// busiest first. Threads are left out so they are not counted twice.
+    pub fn user_summaries(&self) -> Vec<UserSummary> {
+        let mut totals: HashMap<String, UserSummary> = HashMap::new();
+        for pid in &self.process_list {
+            let Some(p) = self.sys.process(*pid) else { continue };
+            if p.thread_kind().is_some() {
+                continue;
+            }
+            let name = user_name(&self.users, p.user_id());
+            let entry = totals.entry(name.clone()).or_insert(UserSummary { name, processes: 0, cpu: 0.0, memory: 0 });
+            entry.processes += 1;
+            entry.cpu += p.cpu_usage();
+            entry.memory += p.memory();
+        }
+        let mut summaries: Vec<UserSummary> = totals.into_values().collect();
+        summaries.sort_by(|a, b| b.cpu.total_cmp(&a.cpu).then_with(|| b.memory.cmp(&a.memory)).then_with(|| a.name.cmp(&b.name)));
+        summaries
+    }
+
+    pub fn toggle_user_summary(&mut self) {
+        self.show_user_summary = !self.show_user_summary;
+    }
+
+    pub fn toggle_disk_io(&mut self) {
+        self.show_disk_io = !self.show_disk_io;
+    }
+
+    pub fn toggle_help(&mut self) {
+        self.show_help = !self.show_help;
+        self.help_scroll = 0;
+    }
+
+    /// Scrolls the help popup; the renderer clamps it to the content.
+    pub fn scroll_help(&mut self, delta: isize) {
+        self.help_scroll = self.help_scroll.saturating_add_signed(delta);
+    }
+
+    pub fn toggle_cpu_details(&mut self) {
+        self.show_cpu_details = !self.show_cpu_details;
+        self.cpu_details_scroll = 0;
+        if self.show_cpu_details {
+            self.cpu_freq_info = (0..self.sys.cpus().len()).map(CpuFreqInfo::read).collect();
+        }
+    }
+
+    pub fn scroll_cpu_details(&mut self, delta: isize) {
+        let max = self.sys.cpus().len().saturating_sub(1);
+        self.cpu_details_scroll = self.cpu_details_scroll.saturating_add_signed(delta).min(max);
+    }
+
+    pub fn close_signal_dialog(&mut self) {
+        self.signal_dialog = None;
+    }
+
+    pub fn signal_dialog_next(&mut self) {
+        if let Some(dialog) = self.signal_dialog.as_mut().filter(|d| !d.confirming) {
+            dialog.selected = (dialog.selected + 1) % SIGNALS.len();
+        }
+    }
+
+    pub fn signal_dialog_previous(&mut self) {
+        if let Some(dialog) = self.signal_dialog.as_mut().filter(|d| !d.confirming) {
+            dialog.selected = (dialog.selected + SIGNALS.len() - 1) % SIGNALS.len();
+        }
+    }
+
+    /// First press asks for confirmation, second press sends the signal.
+    pub fn signal_dialog_accept(&mut self) {
+        let Some(dialog) = self.signal_dialog.as_mut() else { return };
+        if !dialog.confirming {
+            dialog.confirming = true;
+            return;
+        }
+
+        let (signal, signal_name) = SIGNALS[dialog.selected];
+        let pid = dialog.pid;
+        let name = dialog.name.clone();
+        self.signal_dialog = None;
+
+        let message = match self.sys.process(pid).map(|p| p.kill_with(signal)) {
+            None => format!("Process {} ({}) no longer exists", pid, name),
+            Some(None) => format!("{} is not supported on this platform", signal_name),
+            Some(Some(true)) => format!("Sent {} to {} ({})", signal_name, pid, name),
+            Some(Some(false)) => format!(
+                "Failed to send {} to {} ({}): {}",
+                signal_name, pid, name, std::io::Error::last_os_error()
+            ),
+        };
+        self.set_status(message);
+    }
+
+    pub fn set_status(&mut self, message: String) {
+        self.status_message = Some((message, Instant::now()));
+    }
+
+    /// Adds `message` to the status line instead of replacing a message
+    /// that is still shown, so startup warnings do not hide each other.
+    pub fn append_status(&mut self, message: String) {
+        match self.status() {
+            Some(current) => self.set_status(format!("{}; {}", current, message)),
+            None => self.set_status(message),
+        }
+    }
+
+    /// Returns the status line text while it is still fresh.
+    pub fn status(&self) -> Option<&str> {
+        self.status_message
+            .as_ref()
+            .filter(|(_, at)| at.elapsed() < STATUS_TIMEOUT)
+            .map(|(msg, _)| msg.as_str())
     }
 
     pub fn next_theme(&mut self) {
@@ -154,6 +1145,7 @@ impl App {
         } else {
             self.selected_theme_index += 1;
         }
+        self.theme_changed = true;
         self.apply_theme();
     }
 
@@ -163,9 +1155,19 @@ impl App {
         } else {
             self.selected_theme_index -= 1;
         }
+        self.theme_changed = true;
         self.apply_theme();
     }
 
+    /// Switches to the theme called `name`, if it exists, without counting
+    /// it as a change to save.
+    pub fn set_theme(&mut self, name: &str) {
+        if let Some(index) = self.theme_list.iter().position(|t| t == name) {
+            self.selected_theme_index = index;
+            self.apply_theme();
+        }
+    }
+
     fn apply_theme(&mut self) {
         let theme_name = &self.theme_list[self.selected_theme_index];
         if let Some(theme) = self.themes.get(theme_name) {
@@ -175,9 +1177,34 @@ impl App {
     
     pub fn next_layout(&mut self) {
         self.layout_mode = self.layout_mode.next();
+        self.layout_changed = true;
+        self.maximized_panel = None;
+    }
+
+    pub fn toggle_maximized(&mut self, panel: Panel) {
+        self.maximized_panel = if self.maximized_panel == Some(panel) { None } else { Some(panel) };
     }
 
     pub fn quit(&mut self) {
         self.should_quit = true;
     }
+
+    /// Copies the runtime state (theme, layout, sort...) into the config and
+    /// writes it to the config path, if there is one. Theme and layout are
+    /// only written when changed with t/T/l, so command-line overrides stay
+    /// out of the file.
+    pub fn save_config(&mut self) -> anyhow::Result<()> {
+        let Some(path) = self.config_path.clone() else { return Ok(()) };
+        if self.theme_changed {
+            self.config.theme = self.current_theme.name.clone();
+        }
+        if self.layout_changed {
+            self.config.layout = self.layout_mode;
+        }
+        self.config.processes.sort = self.sort_column;
+        self.config.processes.sort_descending = self.sort_descending;
+        self.config.processes.tree = self.tree_mode;
+        self.config.network.interface = self.selected_interface.clone();
+        self.config.save(&path)
+    }
 }
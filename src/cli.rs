@@ -0,0 +1,129 @@
+use crate::app::LayoutMode;
+use crate::import::SchemeFormat;
+use clap::{error::ErrorKind, CommandFactory, Parser, Subcommand};
+use std::path::PathBuf;
+use sysinfo::{Uid, Users};
+
+/// A modern, cross-platform TUI system monitor.
+#[derive(Parser)]
+#[command(version, about)]
+pub struct Cli {
+    /// Color theme to start with (see --list-themes)
+    #[arg(short, long)]
+    pub theme: Option<String>,
+
+    /// Layout to start with
+    #[arg(short, long, value_enum)]
+    pub layout: Option<LayoutMode>,
+
+    /// Refresh interval in milliseconds
+    #[arg(short, long, value_name = "MS", value_parser = clap::value_parser!(u64).range(100..))]
+    pub interval: Option<u64>,
+
+    /// Path to the config file [default: ~/.config/xtop/config.toml]
+    #[arg(short, long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Initial process filter (same syntax as the `/` prompt)
+    #[arg(short, long, value_name = "TEXT")]
+    pub filter: Option<String>,
+
+    /// Only show these PIDs in the process table (repeatable or comma separated)
+    #[arg(short, long = "pid", value_name = "PID", value_delimiter = ',')]
+    pub pids: Vec<usize>,
+
+    /// Only show processes owned by this user (name or numeric UID)
+    #[arg(short, long, value_name = "USER")]
+    pub user: Option<String>,
+
+    /// Print the available theme names and exit
+    #[arg(long)]
+    pub list_themes: bool,
+
+    /// Do not capture the mouse
+    #[arg(long)]
+    pub no_mouse: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Convert a terminal color scheme into an xtop theme file
+    ImportTheme {
+        /// Scheme file (Alacritty TOML/YAML, Kitty, Xresources or base16 YAML)
+        file: PathBuf,
+
+        /// Scheme format [default: detected from the file]
+        #[arg(short, long, value_enum)]
+        format: Option<SchemeFormat>,
+
+        /// Theme name [default: the scheme's name or the file name]
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Output file or directory, `-` for stdout [default: the themes directory]
+        #[arg(short, long, value_name = "PATH")]
+        output: Option<PathBuf>,
+
+        /// Overwrite an existing theme file
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+impl Cli {
+    /// Exits with a clap-formatted usage error.
+    pub fn fail(message: String) -> ! {
+        Cli::command().error(ErrorKind::InvalidValue, message).exit()
+    }
+
+    /// Checks `--theme` against the known theme names, suggesting the closest ones.
+    pub fn validate_theme(&self, themes: &[String]) {
+        let Some(theme) = &self.theme else { return };
+        if themes.contains(theme) {
+            return;
+        }
+        let mut close: Vec<&String> = themes.iter().filter(|t| edit_distance(t, theme) <= 2).collect();
+        close.sort_by_key(|t| edit_distance(t, theme));
+        let hint = match close.first() {
+            Some(name) => format!("\n\n  tip: did you mean '{}'?", name),
+            None => String::new(),
+        };
+        Cli::fail(format!(
+            "unknown theme '{}'{}\n\n  available themes: {}",
+            theme,
+            hint,
+            themes.join(", ")
+        ));
+    }
+
+    /// Resolves `--user` to a UID, accepting either a login name or a number.
+    pub fn resolve_user(&self) -> Option<Uid> {
+        let user = self.user.as_ref()?;
+        let users = Users::new_with_refreshed_list();
+        if let Some(found) = users.iter().find(|u| u.name() == user) {
+            return Some(found.id().clone());
+        }
+        match user.parse::<Uid>() {
+            Ok(uid) if user.chars().all(|c| c.is_ascii_digit()) => Some(uid),
+            _ => Cli::fail(format!("unknown user '{}'", user)),
+        }
+    }
+}
+
+/// Levenshtein distance, used for "did you mean" hints.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            current[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(current[j] + 1);
+        }
+        prev = current;
+    }
+    prev[b.len()]
+}
@@ -0,0 +1,186 @@
+use crate::app::{LayoutMode, Panel, SortColumn};
+use crate::keymap::KeysConfig;
+use crate::units::Units;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Persistent user preferences, read from `config.toml`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub theme: String,
+    pub layout: LayoutMode,
+    /// Refresh interval in milliseconds.
+    pub tick_rate_ms: u64,
+    /// Number of samples kept for the history charts.
+    pub history_length: usize,
+    /// Write runtime changes (theme, layout, sort...) back to the file on exit.
+    pub save_on_exit: bool,
+    /// Directory scanned for `*.json` themes; defaults to `<config dir>/themes`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub themes_dir: Option<PathBuf>,
+    pub panels: PanelConfig,
+    pub processes: ProcessConfig,
+    pub network: NetworkConfig,
+    pub storage: StorageConfig,
+    pub units: Units,
+    pub keys: KeysConfig,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PanelConfig {
+    pub cpu: bool,
+    pub memory: bool,
+    pub storage: bool,
+    pub network: bool,
+    /// Hidden automatically when the system reports no sensors.
+    pub sensors: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProcessConfig {
+    pub columns: Vec<SortColumn>,
+    pub sort: SortColumn,
+    pub sort_descending: bool,
+    pub tree: bool,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NetworkConfig {
+    /// Hide loopback, bridge, tunnel and other virtual interfaces.
+    pub hide_virtual: bool,
+    /// Interface shown in the network summary and chart; all when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interface: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageConfig {
+    /// Filesystem types left out of the storage list.
+    pub hide_filesystems: Vec<String>,
+    /// Mount point prefixes left out of the storage list.
+    pub hide_mounts: Vec<String>,
+    /// Order mounts by percentage used instead of mount point.
+    pub sort_by_usage: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            theme: "x".to_string(),
+            layout: LayoutMode::Dashboard,
+            tick_rate_ms: 1000,
+            history_length: 100,
+            save_on_exit: false,
+            themes_dir: None,
+            panels: PanelConfig::default(),
+            processes: ProcessConfig::default(),
+            network: NetworkConfig::default(),
+            storage: StorageConfig::default(),
+            units: Units::default(),
+            keys: KeysConfig::default(),
+        }
+    }
+}
+
+impl Default for PanelConfig {
+    fn default() -> Self {
+        PanelConfig {
+            cpu: true,
+            memory: true,
+            storage: true,
+            network: true,
+            sensors: true,
+        }
+    }
+}
+
+impl Default for ProcessConfig {
+    fn default() -> Self {
+        ProcessConfig {
+            columns: SortColumn::ALL.to_vec(),
+            sort: SortColumn::Cpu,
+            sort_descending: true,
+            tree: false,
+        }
+    }
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        StorageConfig {
+            hide_filesystems: ["tmpfs", "devtmpfs", "ramfs", "overlay", "squashfs"]
+                .iter()
+                .map(|fs| fs.to_string())
+                .collect(),
+            hide_mounts: vec!["/snap/".to_string()],
+            sort_by_usage: false,
+        }
+    }
+}
+
+impl PanelConfig {
+    pub fn shows(&self, panel: Panel) -> bool {
+        match panel {
+            Panel::Cpu => self.cpu,
+            Panel::Memory => self.memory,
+            Panel::Storage => self.storage,
+            Panel::Network => self.network,
+            Panel::Sensors => self.sensors,
+        }
+    }
+}
+
+impl Config {
+    /// Loads the config from `path`, falling back to defaults when the file
+    /// does not exist. A file that exists but cannot be parsed is an error.
+    pub fn load(path: &Path) -> Result<Config> {
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("failed to read config file {}", path.display()))?;
+        let mut config: Config = toml::from_str(&text)
+            .with_context(|| format!("failed to parse config file {}", path.display()))?;
+        config.tick_rate_ms = config.tick_rate_ms.max(100);
+        config.history_length = config.history_length.max(2);
+        if config.processes.columns.is_empty() {
+            config.processes.columns = SortColumn::ALL.to_vec();
+        }
+        Ok(config)
+    }
+
+    pub fn themes_dir(&self) -> Option<PathBuf> {
+        self.themes_dir.clone().or_else(|| config_dir().map(|dir| dir.join("themes")))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create config directory {}", dir.display()))?;
+        }
+        let text = toml::to_string_pretty(self).context("failed to serialize config")?;
+        fs::write(path, text).with_context(|| format!("failed to write config file {}", path.display()))
+    }
+}
+
+/// Directory holding xtop's configuration: `$XDG_CONFIG_HOME/xtop`,
+/// `~/.config/xtop`, or `%APPDATA%\xtop` on Windows.
+pub fn config_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))?;
+    Some(base.join("xtop"))
+}
+
+pub fn default_config_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("config.toml"))
+}
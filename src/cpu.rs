@@ -0,0 +1,222 @@
+/// Cumulative CPU time counters, in clock ticks, from the aggregate `cpu`
+/// line of `/proc/stat`.
+#[derive(Clone, Copy, Default)]
+pub struct CpuTimes {
+    user: u64,
+    nice: u64,
+    system: u64,
+    idle: u64,
+    iowait: u64,
+    irq: u64,
+    softirq: u64,
+    steal: u64,
+}
+
+/// Share of CPU time spent in each state between two samples, in percent.
+#[derive(Clone, Copy, Default)]
+pub struct CpuBreakdown {
+    pub user: f64,
+    pub system: f64,
+    pub iowait: f64,
+    pub steal: f64,
+}
+
+impl CpuTimes {
+    /// Reads the current counters. Only Linux exposes them; elsewhere this
+    /// returns `None` and the breakdown is not shown.
+    #[cfg(target_os = "linux")]
+    pub fn read() -> Option<CpuTimes> {
+        let stat = std::fs::read_to_string("/proc/stat").ok()?;
+        let line = stat.lines().find(|l| l.starts_with("cpu "))?;
+        let values: Vec<u64> = line.split_whitespace().skip(1).filter_map(|v| v.parse().ok()).collect();
+        let get = |i: usize| values.get(i).copied().unwrap_or(0);
+        Some(CpuTimes {
+            user: get(0),
+            nice: get(1),
+            system: get(2),
+            idle: get(3),
+            iowait: get(4),
+            irq: get(5),
+            softirq: get(6),
+            steal: get(7),
+        })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn read() -> Option<CpuTimes> {
+        None
+    }
+
+    fn total(&self) -> u64 {
+        self.user + self.nice + self.system + self.idle + self.iowait + self.irq + self.softirq + self.steal
+    }
+
+    /// Percentages of the time elapsed between `previous` and `self`.
+    pub fn breakdown_since(&self, previous: &CpuTimes) -> CpuBreakdown {
+        let total = self.total().saturating_sub(previous.total());
+        if total == 0 {
+            return CpuBreakdown::default();
+        }
+        let pct = |now: u64, before: u64| now.saturating_sub(before) as f64 / total as f64 * 100.0;
+        CpuBreakdown {
+            user: pct(self.user + self.nice, previous.user + previous.nice),
+            system: pct(
+                self.system + self.irq + self.softirq,
+                previous.system + previous.irq + previous.softirq,
+            ),
+            iowait: pct(self.iowait, previous.iowait),
+            steal: pct(self.steal, previous.steal),
+        }
+    }
+}
+
+/// How logical CPUs are grouped in the CPU panel.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CpuGrouping {
+    None,
+    Core,
+    Socket,
+    Numa,
+}
+
+impl CpuGrouping {
+    pub fn next(&self) -> Self {
+        match self {
+            CpuGrouping::None => CpuGrouping::Core,
+            CpuGrouping::Core => CpuGrouping::Socket,
+            CpuGrouping::Socket => CpuGrouping::Numa,
+            CpuGrouping::Numa => CpuGrouping::None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CpuGrouping::None => "none",
+            CpuGrouping::Core => "core",
+            CpuGrouping::Socket => "socket",
+            CpuGrouping::Numa => "numa",
+        }
+    }
+}
+
+/// Physical location of each logical CPU, indexed like `System::cpus()`.
+/// Entries are `None` where the platform does not report them.
+#[derive(Default)]
+pub struct CpuTopology {
+    pub core: Vec<Option<usize>>,
+    pub socket: Vec<Option<usize>>,
+    pub numa: Vec<Option<usize>>,
+}
+
+impl CpuTopology {
+    #[cfg(target_os = "linux")]
+    pub fn read(count: usize) -> CpuTopology {
+        let read_id = |cpu: usize, file: &str| -> Option<usize> {
+            let path = format!("/sys/devices/system/cpu/cpu{}/topology/{}", cpu, file);
+            std::fs::read_to_string(path).ok()?.trim().parse().ok()
+        };
+        let numa_node = |cpu: usize| -> Option<usize> {
+            let dir = std::fs::read_dir(format!("/sys/devices/system/cpu/cpu{}", cpu)).ok()?;
+            dir.filter_map(|e| e.ok())
+                .find_map(|e| e.file_name().to_str()?.strip_prefix("node")?.parse().ok())
+        };
+        CpuTopology {
+            core: (0..count).map(|i| read_id(i, "core_id")).collect(),
+            socket: (0..count).map(|i| read_id(i, "physical_package_id")).collect(),
+            numa: (0..count).map(numa_node).collect(),
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn read(count: usize) -> CpuTopology {
+        CpuTopology {
+            core: vec![None; count],
+            socket: vec![None; count],
+            numa: vec![None; count],
+        }
+    }
+
+    /// Number of distinct sockets, when every CPU reports one.
+    pub fn sockets(&self) -> Option<usize> {
+        distinct(&self.socket)
+    }
+
+    /// Number of distinct NUMA nodes, when every CPU reports one.
+    pub fn numa_nodes(&self) -> Option<usize> {
+        distinct(&self.numa)
+    }
+
+    /// Logical CPU indices split into labelled groups. Without topology
+    /// information everything ends up in a single unlabelled group.
+    pub fn groups(&self, count: usize, grouping: CpuGrouping) -> Vec<(String, Vec<usize>)> {
+        let key = |i: usize| -> Option<(usize, usize)> {
+            let socket = self.socket.get(i).copied().flatten();
+            match grouping {
+                CpuGrouping::None => None,
+                CpuGrouping::Socket => socket.map(|s| (s, 0)),
+                CpuGrouping::Numa => self.numa.get(i).copied().flatten().map(|n| (n, 0)),
+                CpuGrouping::Core => self.core.get(i).copied().flatten().map(|c| (socket.unwrap_or(0), c)),
+            }
+        };
+        if grouping == CpuGrouping::None || (0..count).any(|i| key(i).is_none()) {
+            return vec![(String::new(), (0..count).collect())];
+        }
+
+        let mut groups: Vec<((usize, usize), Vec<usize>)> = vec![];
+        for i in 0..count {
+            let k = key(i).unwrap_or_default();
+            match groups.iter_mut().find(|(gk, _)| *gk == k) {
+                Some((_, members)) => members.push(i),
+                None => groups.push((k, vec![i])),
+            }
+        }
+        groups.sort_by_key(|(k, _)| *k);
+        groups
+            .into_iter()
+            .map(|((a, b), members)| {
+                let label = match grouping {
+                    CpuGrouping::Socket => format!("S{}", a),
+                    CpuGrouping::Numa => format!("N{}", a),
+                    _ => format!("S{}C{}", a, b),
+                };
+                (label, members)
+            })
+            .collect()
+    }
+}
+
+fn distinct(ids: &[Option<usize>]) -> Option<usize> {
+    let ids: Option<std::collections::BTreeSet<usize>> = ids.iter().copied().collect();
+    ids.map(|ids| ids.len()).filter(|&n| n > 0)
+}
+
+/// Frequency limits and scaling governor of one logical CPU, in MHz, from
+/// `/sys/devices/system/cpu/cpuN/cpufreq`. Empty on other platforms.
+#[derive(Clone, Default)]
+pub struct CpuFreqInfo {
+    pub min_mhz: Option<u64>,
+    pub max_mhz: Option<u64>,
+    pub governor: Option<String>,
+}
+
+impl CpuFreqInfo {
+    #[cfg(target_os = "linux")]
+    pub fn read(cpu: usize) -> CpuFreqInfo {
+        let read = |file: &str| {
+            std::fs::read_to_string(format!("/sys/devices/system/cpu/cpu{}/cpufreq/{}", cpu, file))
+                .ok()
+                .map(|s| s.trim().to_string())
+        };
+        let khz = |file: &str| read(file)?.parse::<u64>().ok().map(|k| k / 1000);
+        CpuFreqInfo {
+            min_mhz: khz("cpuinfo_min_freq"),
+            max_mhz: khz("cpuinfo_max_freq"),
+            governor: read("scaling_governor"),
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn read(_cpu: usize) -> CpuFreqInfo {
+        CpuFreqInfo::default()
+    }
+}
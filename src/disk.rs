@@ -0,0 +1,90 @@
+use std::collections::BTreeMap;
+
+/// Cumulative I/O counters of one block device from `/proc/diskstats`.
+#[derive(Clone, Copy, Default)]
+pub struct DiskCounters {
+    reads: u64,
+    read_bytes: u64,
+    writes: u64,
+    written_bytes: u64,
+    /// Milliseconds spent with I/O in flight.
+    busy_ms: u64,
+}
+
+/// Per-second activity of one device between two samples. IOPS and busy
+/// time are only known where the platform reports them.
+#[derive(Clone, Copy, Default)]
+pub struct DiskRates {
+    pub read_bytes: f64,
+    pub write_bytes: f64,
+    pub read_iops: Option<f64>,
+    pub write_iops: Option<f64>,
+    /// Share of the interval the device was busy, in percent.
+    pub busy: Option<f64>,
+}
+
+impl DiskCounters {
+    /// Reads the counters of every whole block device that has seen any
+    /// I/O. Partitions are skipped so activity is not counted twice.
+    #[cfg(target_os = "linux")]
+    pub fn read_all() -> Option<BTreeMap<String, DiskCounters>> {
+        const SECTOR: u64 = 512;
+        let stats = std::fs::read_to_string("/proc/diskstats").ok()?;
+        let mut devices = BTreeMap::new();
+        for line in stats.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let Some(name) = fields.get(2) else { continue };
+            if !std::path::Path::new("/sys/block").join(name).exists() {
+                continue;
+            }
+            let get = |i: usize| fields.get(i).and_then(|v| v.parse::<u64>().ok()).unwrap_or(0);
+            let counters = DiskCounters {
+                reads: get(3),
+                read_bytes: get(5) * SECTOR,
+                writes: get(7),
+                written_bytes: get(9) * SECTOR,
+                busy_ms: get(12),
+            };
+            if counters.reads + counters.writes > 0 {
+                devices.insert(name.to_string(), counters);
+            }
+        }
+        Some(devices)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn read_all() -> Option<BTreeMap<String, DiskCounters>> {
+        None
+    }
+
+    pub fn rates_since(&self, previous: &DiskCounters, elapsed_secs: f64) -> DiskRates {
+        let rate = |now: u64, before: u64| now.saturating_sub(before) as f64 / elapsed_secs;
+        DiskRates {
+            read_bytes: rate(self.read_bytes, previous.read_bytes),
+            write_bytes: rate(self.written_bytes, previous.written_bytes),
+            read_iops: Some(rate(self.reads, previous.reads)),
+            write_iops: Some(rate(self.writes, previous.writes)),
+            busy: Some((rate(self.busy_ms, previous.busy_ms) / 10.0).min(100.0)),
+        }
+    }
+}
+
+/// Used and total inodes of the filesystem mounted at `path`.
+#[cfg(unix)]
+pub fn inode_usage(path: &std::path::Path) -> Option<(u64, u64)> {
+    use std::os::unix::ffi::OsStrExt;
+    let path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: `path` is NUL-terminated and `stat` is a valid out pointer
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    let total = stat.f_files as u64;
+    let free = stat.f_ffree as u64;
+    (total > 0).then(|| (total.saturating_sub(free), total))
+}
+
+#[cfg(not(unix))]
+pub fn inode_usage(_path: &std::path::Path) -> Option<(u64, u64)> {
+    None
+}
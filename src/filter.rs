@@ -0,0 +1,104 @@
+use crate::app::process_user;
+use regex::{Regex, RegexBuilder};
+use sysinfo::{Process, Users};
+
+/// Live filter applied to the process table, edited from the `/` prompt.
+pub struct ProcessFilter {
+    pub query: String,
+    pub regex: bool,
+    pub case_insensitive: bool,
+    pub error: Option<String>,
+    compiled: Option<Regex>,
+}
+
+impl ProcessFilter {
+    pub fn new() -> ProcessFilter {
+        ProcessFilter {
+            query: String::new(),
+            regex: false,
+            case_insensitive: true,
+            error: None,
+            compiled: None,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.query.is_empty()
+    }
+
+    pub fn set_query(&mut self, query: &str) {
+        self.query = query.to_string();
+        self.recompile();
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.query.push(c);
+        self.recompile();
+    }
+
+    pub fn pop(&mut self) {
+        self.query.pop();
+        self.recompile();
+    }
+
+    pub fn clear(&mut self) {
+        self.query.clear();
+        self.recompile();
+    }
+
+    pub fn toggle_regex(&mut self) {
+        self.regex = !self.regex;
+        self.recompile();
+    }
+
+    pub fn toggle_case(&mut self) {
+        self.case_insensitive = !self.case_insensitive;
+        self.recompile();
+    }
+
+    fn recompile(&mut self) {
+        self.error = None;
+        self.compiled = None;
+        if !self.regex || self.query.is_empty() {
+            return;
+        }
+        match RegexBuilder::new(&self.query).case_insensitive(self.case_insensitive).build() {
+            Ok(re) => self.compiled = Some(re),
+            Err(_) => self.error = Some("invalid regex".to_string()),
+        }
+    }
+
+    /// Matches the query against the name, command line, PID and user (UID or name) of a process.
+    /// An empty query or an invalid regex matches everything.
+    pub fn matches(&self, process: &Process, users: &Users) -> bool {
+        if !self.is_active() || self.error.is_some() {
+            return true;
+        }
+
+        let cmd = process
+            .cmd()
+            .iter()
+            .map(|arg| arg.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let uid = process.user_id().map(|u| u.to_string()).unwrap_or_default();
+        let fields = [
+            process.name().to_string_lossy().into_owned(),
+            cmd,
+            process.pid().to_string(),
+            uid,
+            process_user(users, process),
+        ];
+
+        if let Some(re) = &self.compiled {
+            return fields.iter().any(|f| re.is_match(f));
+        }
+
+        if self.case_insensitive {
+            let query = self.query.to_lowercase();
+            fields.iter().any(|f| f.to_lowercase().contains(&query))
+        } else {
+            fields.iter().any(|f| f.contains(&self.query))
+        }
+    }
+}
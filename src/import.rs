@@ -0,0 +1,515 @@
+use crate::theme::hex_to_color;
+use anyhow::{anyhow, bail, Context, Result};
+use clap::ValueEnum;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Terminal color scheme formats understood by `xtop import-theme`.
+#[derive(Clone, Copy, PartialEq, Debug, ValueEnum)]
+pub enum SchemeFormat {
+    /// Alacritty `colors` section, TOML or YAML
+    Alacritty,
+    /// Kitty `colorN` / `background` / `foreground` lines
+    Kitty,
+    /// X resources `*.colorN` entries
+    Xresources,
+    /// base16 scheme YAML (`base00`..`base0F`)
+    Base16,
+}
+
+/// A 16-color terminal scheme with optional primary colors, as `#rrggbb` strings.
+pub struct Scheme {
+    pub name: Option<String>,
+    pub colors: [String; 16],
+    pub background: Option<String>,
+    pub foreground: Option<String>,
+}
+
+const ANSI_NAMES: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+
+/// base16 slot feeding each of the 16 ANSI colors, as used by base16-shell.
+const BASE16_ANSI: [&str; 16] = [
+    "base00", "base08", "base0B", "base0A", "base0D", "base0E", "base0C", "base05",
+    "base03", "base08", "base0B", "base0A", "base0D", "base0E", "base0C", "base07",
+];
+
+impl Scheme {
+    /// Serializes the scheme in the `references.md` theme schema.
+    pub fn to_theme_json(&self, name: &str) -> String {
+        let mut lines = vec![format!("    \"name\": {}", serde_json::Value::from(name))];
+        for (i, color) in self.colors.iter().enumerate() {
+            let key = format!("\"color{}\":", i);
+            lines.push(format!("    {:<10} \"{}\"", key, color));
+        }
+        if let Some(bg) = &self.background {
+            lines.push(format!("    \"background\": \"{}\"", bg));
+        }
+        if let Some(fg) = &self.foreground {
+            lines.push(format!("    \"foreground\": \"{}\"", fg));
+        }
+        format!("{{\n{}\n}}\n", lines.join(",\n"))
+    }
+}
+
+/// Guesses the format of `path` from its extension and contents.
+pub fn detect_format(path: &Path, text: &str) -> Option<SchemeFormat> {
+    let ext = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    let name = path.file_name().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default();
+    if ext == "toml" {
+        return Some(SchemeFormat::Alacritty);
+    }
+    if ext == "yml" || ext == "yaml" {
+        return Some(if text.contains("base00") { SchemeFormat::Base16 } else { SchemeFormat::Alacritty });
+    }
+    if name.contains("xresources") || name.contains("xdefaults") || text.lines().any(|l| l.trim_start().starts_with('*')) {
+        return Some(SchemeFormat::Xresources);
+    }
+    if ext == "conf" || text.lines().any(|l| l.trim_start().starts_with("color0 ") || l.trim_start().starts_with("color0\t")) {
+        return Some(SchemeFormat::Kitty);
+    }
+    None
+}
+
+pub fn parse_scheme(format: SchemeFormat, text: &str) -> Result<Scheme> {
+    match format {
+        SchemeFormat::Alacritty => parse_alacritty(text),
+        SchemeFormat::Kitty => parse_kitty(text),
+        SchemeFormat::Xresources => parse_xresources(text),
+        SchemeFormat::Base16 => parse_base16(text),
+    }
+}
+
+/// Accepts `#rrggbb`, `0xrrggbb` and bare `rrggbb`, returning `#rrggbb`.
+fn normalize_color(value: &str) -> Result<String> {
+    let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+    let digits = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+        .or_else(|| value.strip_prefix('#'))
+        .unwrap_or(value);
+    let hex = format!("#{}", digits.to_lowercase());
+    hex_to_color(&hex).map_err(|e| anyhow!(e))?;
+    Ok(hex)
+}
+
+fn ansi_from_map(map: &HashMap<String, String>, key: impl Fn(usize) -> String) -> Result<[String; 16]> {
+    let mut colors: [String; 16] = Default::default();
+    for (i, slot) in colors.iter_mut().enumerate() {
+        let key = key(i);
+        let value = map.get(&key).ok_or_else(|| anyhow!("missing '{}'", key))?;
+        *slot = normalize_color(value).with_context(|| format!("in '{}'", key))?;
+    }
+    Ok(colors)
+}
+
+fn optional_color(map: &HashMap<String, String>, key: &str) -> Result<Option<String>> {
+    map.get(key)
+        .map(|v| normalize_color(v).with_context(|| format!("in '{}'", key)))
+        .transpose()
+}
+
+fn alacritty_key(i: usize) -> String {
+    let group = if i < 8 { "normal" } else { "bright" };
+    format!("colors.{}.{}", group, ANSI_NAMES[i % 8])
+}
+
+fn parse_alacritty(text: &str) -> Result<Scheme> {
+    // Alacritty switched from YAML to TOML; flatten either into dotted keys
+    let map = match toml::from_str::<toml::Table>(text) {
+        Ok(table) => {
+            let mut map = HashMap::new();
+            flatten_toml("", &toml::Value::Table(table), &mut map);
+            map
+        }
+        Err(_) => parse_simple_yaml(text),
+    };
+    Ok(Scheme {
+        name: None,
+        colors: ansi_from_map(&map, alacritty_key)?,
+        background: optional_color(&map, "colors.primary.background")?,
+        foreground: optional_color(&map, "colors.primary.foreground")?,
+    })
+}
+
+fn flatten_toml(prefix: &str, value: &toml::Value, out: &mut HashMap<String, String>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table {
+                let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                flatten_toml(&path, value, out);
+            }
+        }
+        toml::Value::String(s) => {
+            out.insert(prefix.to_string(), s.clone());
+        }
+        _ => {}
+    }
+}
+
+/// Reads the nested `key: value` maps used by color scheme YAML files into
+/// dotted keys. Lists, anchors and multi-line scalars are not supported.
+fn parse_simple_yaml(text: &str) -> HashMap<String, String> {
+    let mut map = HashMap::new();
+    let mut stack: Vec<(usize, String)> = vec![];
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with("---") {
+            continue;
+        }
+        let indent = line.len() - trimmed.len();
+        let Some((key, value)) = trimmed.split_once(':') else { continue };
+        let key = key.trim().trim_matches(|c| c == '"' || c == '\'').to_string();
+
+        while stack.last().is_some_and(|(level, _)| *level >= indent) {
+            stack.pop();
+        }
+        let value = strip_yaml_comment(value.trim());
+        if value.is_empty() {
+            stack.push((indent, key));
+            continue;
+        }
+        let mut path: Vec<&str> = stack.iter().map(|(_, k)| k.as_str()).collect();
+        path.push(&key);
+        map.insert(path.join("."), value.trim_matches(|c| c == '"' || c == '\'').to_string());
+    }
+    map
+}
+
+fn strip_yaml_comment(value: &str) -> &str {
+    if value.starts_with('"') || value.starts_with('\'') {
+        let quote = value.chars().next().unwrap_or('"');
+        return match value[1..].find(quote) {
+            Some(end) => &value[..end + 2],
+            None => value,
+        };
+    }
+    match value.find(" #") {
+        Some(pos) => value[..pos].trim_end(),
+        None => value,
+    }
+}
+
+fn parse_kitty(text: &str) -> Result<Scheme> {
+    let mut map = HashMap::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut parts = line.split_whitespace();
+        if let (Some(key), Some(value)) = (parts.next(), parts.next()) {
+            map.insert(key.to_string(), value.to_string());
+        }
+    }
+    Ok(Scheme {
+        name: None,
+        colors: ansi_from_map(&map, |i| format!("color{}", i))?,
+        background: optional_color(&map, "background")?,
+        foreground: optional_color(&map, "foreground")?,
+    })
+}
+
+fn parse_xresources(text: &str) -> Result<Scheme> {
+    let mut defines: HashMap<String, String> = HashMap::new();
+    let mut map = HashMap::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(rest) = line.strip_prefix("#define") {
+            let mut parts = rest.split_whitespace();
+            if let (Some(name), Some(value)) = (parts.next(), parts.next()) {
+                defines.insert(name.to_string(), value.to_string());
+            }
+            continue;
+        }
+        if line.is_empty() || line.starts_with('!') || line.starts_with('#') {
+            continue;
+        }
+        let Some((resource, value)) = line.split_once(':') else { continue };
+        // `*.color4`, `*color4`, `URxvt*color4` and `URxvt.color4` all name color4
+        let key = resource.rsplit(['*', '.']).next().unwrap_or(resource).trim();
+        let value = value.trim();
+        let value = defines.get(value).cloned().unwrap_or_else(|| value.to_string());
+        map.insert(key.to_string(), value);
+    }
+    Ok(Scheme {
+        name: None,
+        colors: ansi_from_map(&map, |i| format!("color{}", i))?,
+        background: optional_color(&map, "background")?,
+        foreground: optional_color(&map, "foreground")?,
+    })
+}
+
+fn parse_base16(text: &str) -> Result<Scheme> {
+    let mut map = parse_simple_yaml(text);
+    // base16 schemes use either top-level `baseXX` keys or a `palette` map
+    let nested: Vec<(String, String)> = map
+        .iter()
+        .filter_map(|(k, v)| k.strip_prefix("palette.").map(|k| (k.to_string(), v.clone())))
+        .collect();
+    map.extend(nested);
+    // Slot names are case-insensitive (`base0A` vs `base0a`)
+    let map: HashMap<String, String> = map.into_iter().map(|(k, v)| (k.to_lowercase(), v)).collect();
+
+    let mut colors: [String; 16] = Default::default();
+    for (i, slot) in colors.iter_mut().enumerate() {
+        let key = BASE16_ANSI[i].to_lowercase();
+        let value = map.get(&key).ok_or_else(|| anyhow!("missing '{}'", BASE16_ANSI[i]))?;
+        *slot = normalize_color(value).with_context(|| format!("in '{}'", BASE16_ANSI[i]))?;
+    }
+    Ok(Scheme {
+        name: map.get("scheme").or_else(|| map.get("name")).cloned(),
+        colors,
+        background: optional_color(&map, "base00")?,
+        foreground: optional_color(&map, "base05")?,
+    })
+}
+
+/// File name (without `.json`) for a theme called `name`: lowercase, with
+/// anything outside `[A-Za-z0-9._-]` turned into `-` and no leading dots,
+/// so it can be neither hidden nor a path.
+fn theme_file_stem(name: &str) -> Result<String> {
+    let stem: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') { c } else { '-' })
+        .collect();
+    let stem = stem.trim_start_matches(['.', '-']).to_string();
+    if stem.is_empty() || stem == "." || stem == ".." {
+        bail!("'{}' cannot be used as a theme file name, pass --name", name);
+    }
+    Ok(stem)
+}
+
+/// Reads a terminal color scheme and writes it as an xtop theme file.
+/// `output` of `-` prints the theme to stdout instead.
+pub fn import_theme(
+    input: &Path,
+    format: Option<SchemeFormat>,
+    name: Option<String>,
+    output: &Path,
+    force: bool,
+) -> Result<()> {
+    let text = fs::read_to_string(input).with_context(|| format!("failed to read {}", input.display()))?;
+    let format = match format.or_else(|| detect_format(input, &text)) {
+        Some(format) => format,
+        None => bail!("cannot detect the format of {}, pass --format", input.display()),
+    };
+    let scheme = parse_scheme(format, &text)
+        .with_context(|| format!("failed to import {} as {:?}", input.display(), format))?;
+
+    let stem = input.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_else(|| "imported".to_string());
+    let name = name.or_else(|| scheme.name.clone()).unwrap_or(stem);
+    let json = scheme.to_theme_json(&name);
+
+    if output == Path::new("-") {
+        print!("{}", json);
+        return Ok(());
+    }
+    let target = if output.is_dir() || output.extension().is_none() {
+        let target = output.join(format!("{}.json", theme_file_stem(&name)?));
+        // The stem has no separators, but make sure it cannot leave `output`
+        if target.parent() != Some(output) {
+            bail!("theme name '{}' does not give a file inside {}", name, output.display());
+        }
+        target
+    } else {
+        output.to_path_buf()
+    };
+    if target.exists() && !force {
+        bail!("{} already exists, pass --force to overwrite", target.display());
+    }
+    if let Some(dir) = target.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+    fs::write(&target, json).with_context(|| format!("failed to write {}", target.display()))?;
+    println!("Imported '{}' to {}", name, target.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALACRITTY_TOML: &str = r##"
+[colors.primary]
+background = "#1d1f21"
+foreground = "0xc5c8c6"
+
+[colors.normal]
+black = "#1d1f21"
+red = "#cc6666"
+green = "#b5bd68"
+yellow = "#f0c674"
+blue = "#81a2be"
+magenta = "#b294bb"
+cyan = "#8abeb7"
+white = "#c5c8c6"
+
+[colors.bright]
+black = "#666666"
+red = "#d54e53"
+green = "#b9ca4a"
+yellow = "#e7c547"
+blue = "#7aa6da"
+magenta = "#c397d8"
+cyan = "#70c0b1"
+white = "#EAEAEA"
+"##;
+
+    const ALACRITTY_YAML: &str = r##"
+colors:
+  primary:
+    background: '#1d1f21'
+    foreground: '#c5c8c6'
+  normal:
+    black:   '#1d1f21'
+    red:     '#cc6666'
+    green:   '#b5bd68'
+    yellow:  '#f0c674'
+    blue:    '#81a2be'
+    magenta: '#b294bb'
+    cyan:    '#8abeb7'
+    white:   '#c5c8c6'
+  bright:
+    black:   '#666666' # comment
+    red:     '#d54e53'
+    green:   '#b9ca4a'
+    yellow:  '#e7c547'
+    blue:    '#7aa6da'
+    magenta: '#c397d8'
+    cyan:    '#70c0b1'
+    white:   '#eaeaea'
+"##;
+
+    const KITTY: &str = "\
+# Tomorrow Night
+background #1d1f21
+foreground #c5c8c6
+color0  #1d1f21
+color1  #cc6666
+color2  #b5bd68
+color3  #f0c674
+color4  #81a2be
+color5  #b294bb
+color6  #8abeb7
+color7  #c5c8c6
+color8  #666666
+color9  #d54e53
+color10 #b9ca4a
+color11 #e7c547
+color12 #7aa6da
+color13 #c397d8
+color14 #70c0b1
+color15 #eaeaea
+";
+
+    const XRESOURCES: &str = "\
+! Tomorrow Night
+#define t_background #1d1f21
+#define t_red #cc6666
+*.background: t_background
+*foreground: #c5c8c6
+*.color0: t_background
+*.color1: t_red
+*color2: #b5bd68
+*color3: #f0c674
+URxvt.color4: #81a2be
+URxvt.color5: #b294bb
+URxvt*color6: #8abeb7
+*.color7: #c5c8c6
+*.color8: #666666
+*.color9: #d54e53
+*.color10: #b9ca4a
+*.color11: #e7c547
+*.color12: #7aa6da
+*.color13: #c397d8
+*.color14: #70c0b1
+*.color15: #eaeaea
+";
+
+    const BASE16: &str = "\
+scheme: \"Tomorrow Night\"
+author: \"Chris Kempson\"
+palette:
+  base00: \"1d1f21\"
+  base01: \"282a2e\"
+  base02: \"373b41\"
+  base03: \"969896\"
+  base04: \"b4b7b4\"
+  base05: \"c5c8c6\"
+  base06: \"e0e0e0\"
+  base07: \"ffffff\"
+  base08: \"cc6666\"
+  base09: \"de935f\"
+  base0A: \"f0c674\"
+  base0B: \"b5bd68\"
+  base0C: \"8abeb7\"
+  base0D: \"81a2be\"
+  base0E: \"b294bb\"
+  base0F: \"a3685a\"
+";
+
+    fn assert_tomorrow_night(scheme: &Scheme) {
+        assert_eq!(scheme.colors[0], "#1d1f21");
+        assert_eq!(scheme.colors[1], "#cc6666");
+        assert_eq!(scheme.colors[4], "#81a2be");
+        assert_eq!(scheme.colors[6], "#8abeb7");
+        assert_eq!(scheme.colors[8], "#666666");
+        assert_eq!(scheme.colors[15], "#eaeaea");
+        assert_eq!(scheme.background.as_deref(), Some("#1d1f21"));
+        assert_eq!(scheme.foreground.as_deref(), Some("#c5c8c6"));
+    }
+
+    #[test]
+    fn parses_alacritty_toml() {
+        assert_eq!(detect_format(Path::new("tomorrow.toml"), ALACRITTY_TOML), Some(SchemeFormat::Alacritty));
+        assert_tomorrow_night(&parse_scheme(SchemeFormat::Alacritty, ALACRITTY_TOML).unwrap());
+    }
+
+    #[test]
+    fn parses_alacritty_yaml() {
+        assert_eq!(detect_format(Path::new("tomorrow.yml"), ALACRITTY_YAML), Some(SchemeFormat::Alacritty));
+        assert_tomorrow_night(&parse_scheme(SchemeFormat::Alacritty, ALACRITTY_YAML).unwrap());
+    }
+
+    #[test]
+    fn parses_kitty() {
+        assert_eq!(detect_format(Path::new("tomorrow.conf"), KITTY), Some(SchemeFormat::Kitty));
+        assert_tomorrow_night(&parse_scheme(SchemeFormat::Kitty, KITTY).unwrap());
+    }
+
+    #[test]
+    fn parses_xresources() {
+        assert_eq!(detect_format(Path::new("tomorrow"), XRESOURCES), Some(SchemeFormat::Xresources));
+        assert_tomorrow_night(&parse_scheme(SchemeFormat::Xresources, XRESOURCES).unwrap());
+    }
+
+    #[test]
+    fn parses_base16_palette() {
+        assert_eq!(detect_format(Path::new("tomorrow.yaml"), BASE16), Some(SchemeFormat::Base16));
+        let scheme = parse_scheme(SchemeFormat::Base16, BASE16).unwrap();
+        assert_eq!(scheme.name.as_deref(), Some("Tomorrow Night"));
+        assert_eq!(scheme.colors[1], "#cc6666");
+        assert_eq!(scheme.colors[3], "#f0c674");
+        assert_eq!(scheme.colors[8], "#969896");
+        assert_eq!(scheme.colors[15], "#ffffff");
+        assert_eq!(scheme.background.as_deref(), Some("#1d1f21"));
+        assert_eq!(scheme.foreground.as_deref(), Some("#c5c8c6"));
+    }
+
+    #[test]
+    fn rejects_malformed_color() {
+        let text = KITTY.replace("color3  #f0c674", "color3  #f0c67");
+        let err = parse_scheme(SchemeFormat::Kitty, &text).err().expect("bad color accepted");
+        assert!(format!("{:#}", err).contains("color3"), "{:#}", err);
+    }
+
+    #[test]
+    fn theme_file_stem_stays_in_directory() {
+        assert_eq!(theme_file_stem("Tomorrow Night").unwrap(), "tomorrow-night");
+        assert_eq!(theme_file_stem("../../evil/x").unwrap(), "evil-x");
+        assert!(theme_file_stem("..").is_err());
+        assert!(theme_file_stem("/").is_err());
+    }
+}
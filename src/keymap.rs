@@ -0,0 +1,496 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Something a key can be bound to. Popups and the filter prompt reuse the
+/// navigation actions and have a few fixed keys of their own.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Quit,
+    Help,
+    NextTheme,
+    PreviousTheme,
+    NextLayout,
+    Cancel,
+    CpuView,
+    CpuGrouping,
+    CpuDetails,
+    NextInterface,
+    InterfaceList,
+    VirtualInterfaces,
+    DiskIo,
+    StorageSort,
+    StorageUp,
+    StorageDown,
+    SelectNext,
+    SelectPrevious,
+    PageDown,
+    PageUp,
+    SelectFirst,
+    SelectLast,
+    Details,
+    Signal,
+    NextSortColumn,
+    PreviousSortColumn,
+    ReverseSort,
+    SortByCpu,
+    SortByMemory,
+    SortByTime,
+    Filter,
+    NextMatch,
+    PreviousMatch,
+    TreeView,
+    Collapse,
+    Expand,
+    ToggleCollapse,
+    UserSummary,
+}
+
+/// Where an action applies, for grouping the help popup.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Context {
+    Global,
+    Processes,
+}
+
+impl Action {
+    pub const ALL: [Action; 38] = [
+        Action::Quit,
+        Action::Help,
+        Action::NextTheme,
+        Action::PreviousTheme,
+        Action::NextLayout,
+        Action::Cancel,
+        Action::CpuView,
+        Action::CpuGrouping,
+        Action::CpuDetails,
+        Action::NextInterface,
+        Action::InterfaceList,
+        Action::VirtualInterfaces,
+        Action::DiskIo,
+        Action::StorageSort,
+        Action::StorageUp,
+        Action::StorageDown,
+        Action::SelectNext,
+        Action::SelectPrevious,
+        Action::PageDown,
+        Action::PageUp,
+        Action::SelectFirst,
+        Action::SelectLast,
+        Action::Details,
+        Action::Signal,
+        Action::NextSortColumn,
+        Action::PreviousSortColumn,
+        Action::ReverseSort,
+        Action::SortByCpu,
+        Action::SortByMemory,
+        Action::SortByTime,
+        Action::Filter,
+        Action::NextMatch,
+        Action::PreviousMatch,
+        Action::TreeView,
+        Action::Collapse,
+        Action::Expand,
+        Action::ToggleCollapse,
+        Action::UserSummary,
+    ];
+
+    pub fn context(&self) -> Context {
+        match self {
+            Action::SelectNext
+            | Action::SelectPrevious
+            | Action::PageDown
+            | Action::PageUp
+            | Action::SelectFirst
+            | Action::SelectLast
+            | Action::Details
+            | Action::Signal
+            | Action::NextSortColumn
+            | Action::PreviousSortColumn
+            | Action::ReverseSort
+            | Action::SortByCpu
+            | Action::SortByMemory
+            | Action::SortByTime
+            | Action::Filter
+            | Action::NextMatch
+            | Action::PreviousMatch
+            | Action::TreeView
+            | Action::Collapse
+            | Action::Expand
+            | Action::ToggleCollapse
+            | Action::UserSummary => Context::Processes,
+            _ => Context::Global,
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Quit => "Quit (closes popups)",
+            Action::Help => "Toggle this help",
+            Action::NextTheme => "Next theme",
+            Action::PreviousTheme => "Previous theme",
+            Action::NextLayout => "Next layout",
+            Action::Cancel => "Restore a maximized panel, clear the filter",
+            Action::CpuView => "Cycle the per-core CPU view",
+            Action::CpuGrouping => "Group CPUs by core, socket or NUMA node",
+            Action::CpuDetails => "CPU details",
+            Action::NextInterface => "Cycle network interfaces",
+            Action::InterfaceList => "Toggle the interface table",
+            Action::VirtualInterfaces => "Hide / show virtual interfaces",
+            Action::DiskIo => "Toggle capacity / disk I/O view",
+            Action::StorageSort => "Order mounts by usage / mount point",
+            Action::StorageUp => "Scroll the storage list up",
+            Action::StorageDown => "Scroll the storage list down",
+            Action::SelectNext => "Select next process (scrolls popups)",
+            Action::SelectPrevious => "Select previous process (scrolls popups)",
+            Action::PageDown => "Scroll down by one page",
+            Action::PageUp => "Scroll up by one page",
+            Action::SelectFirst => "First process",
+            Action::SelectLast => "Last process",
+            Action::Details => "Process details",
+            Action::Signal => "Send a signal",
+            Action::NextSortColumn => "Next sort column",
+            Action::PreviousSortColumn => "Previous sort column",
+            Action::ReverseSort => "Reverse the sort direction",
+            Action::SortByCpu => "Sort by CPU",
+            Action::SortByMemory => "Sort by memory",
+            Action::SortByTime => "Sort by run time",
+            Action::Filter => "Filter processes",
+            Action::NextMatch => "Next filter match (tree view)",
+            Action::PreviousMatch => "Previous filter match (tree view)",
+            Action::TreeView => "Toggle the tree view",
+            Action::Collapse => "Collapse the subtree",
+            Action::Expand => "Expand the subtree",
+            Action::ToggleCollapse => "Toggle collapse of the subtree",
+            Action::UserSummary => "Toggle the per-user summary",
+        }
+    }
+}
+
+/// The name used in the config file, e.g. `next_theme`.
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match toml::Value::try_from(self) {
+            Ok(toml::Value::String(name)) => write!(f, "{}", name),
+            _ => write!(f, "{:?}", self),
+        }
+    }
+}
+
+/// A key with its modifiers, written like `q`, `G`, `ctrl+c` or `F9` in
+/// the config. Shift is part of the character for printable keys.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct KeyBinding {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> KeyBinding {
+        let relevant = KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT;
+        let mut modifiers = modifiers & relevant;
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            KeyCode::BackTab => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            code => code,
+        };
+        KeyBinding { code, modifiers }
+    }
+}
+
+impl From<&KeyEvent> for KeyBinding {
+    fn from(key: &KeyEvent) -> KeyBinding {
+        KeyBinding::new(key.code, key.modifiers)
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<KeyBinding, String> {
+        // `+` on its own (or after modifiers, as in `ctrl++`) is the key
+        let (modifiers, key) = match s.strip_suffix("++") {
+            Some(modifiers) => (modifiers, "+"),
+            None => match s.rsplit_once('+') {
+                Some((modifiers, key)) if !key.is_empty() => (modifiers, key),
+                _ => ("", s),
+            },
+        };
+        let mut mods = KeyModifiers::NONE;
+        for modifier in modifiers.split('+').filter(|m| !m.is_empty()) {
+            mods |= match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(format!("unknown modifier '{}' in key '{}'", modifier, s)),
+            };
+        }
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            // Letters in chords are case-insensitive; Shift has to be spelled out
+            (Some(c), None) if mods.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => KeyCode::Char(c.to_ascii_lowercase()),
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_ascii_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "backspace" => KeyCode::Backspace,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "space" => KeyCode::Char(' '),
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pgup" | "pageup" => KeyCode::PageUp,
+                "pgdn" | "pagedown" => KeyCode::PageDown,
+                "insert" | "ins" => KeyCode::Insert,
+                "delete" | "del" => KeyCode::Delete,
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key '{}'", s)),
+                },
+            },
+        };
+        Ok(KeyBinding::new(code, mods))
+    }
+}
+
+impl TryFrom<String> for KeyBinding {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<KeyBinding, String> {
+        s.parse()
+    }
+}
+
+impl From<KeyBinding> for String {
+    fn from(key: KeyBinding) -> String {
+        key.to_string()
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        let chord = self.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            // Chords are written `Ctrl+C`; an uppercase letter there needs Shift
+            KeyCode::Char(c) if chord && c.is_ascii_uppercase() => write!(f, "Shift+{}", c),
+            KeyCode::Char(c) if chord => write!(f, "{}", c.to_ascii_uppercase()),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::BackTab => write!(f, "BackTab"),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// Starting set of bindings, before the overrides from the config.
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyPreset {
+    #[default]
+    Default,
+    /// Adds `g`/`G`, `Ctrl+D`/`Ctrl+U`, `Ctrl+F`/`Ctrl+B` and `h`/`l`.
+    Vim,
+    /// Function keys, `P`/`M`/`T` sorting and `k` to kill, as in htop.
+    Htop,
+}
+
+impl KeyPreset {
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeyPreset::Default => "default",
+            KeyPreset::Vim => "vim",
+            KeyPreset::Htop => "htop",
+        }
+    }
+}
+
+/// The `[keys]` config section.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeysConfig {
+    pub preset: KeyPreset,
+    /// Keys per action, replacing the preset's keys for that action. An
+    /// empty list unbinds it.
+    pub bindings: BTreeMap<Action, Vec<KeyBinding>>,
+}
+
+const DEFAULT_KEYS: &[(Action, &[&str])] = &[
+    (Action::Quit, &["q", "ctrl+c"]),
+    (Action::Help, &["?", "F1"]),
+    (Action::NextTheme, &["t"]),
+    (Action::PreviousTheme, &["T"]),
+    (Action::NextLayout, &["l"]),
+    (Action::Cancel, &["esc"]),
+    (Action::CpuView, &["c"]),
+    (Action::CpuGrouping, &["g"]),
+    (Action::CpuDetails, &["C"]),
+    (Action::NextInterface, &["i"]),
+    (Action::InterfaceList, &["I"]),
+    (Action::VirtualInterfaces, &["V"]),
+    (Action::DiskIo, &["D"]),
+    (Action::StorageSort, &["u"]),
+    (Action::StorageUp, &["["]),
+    (Action::StorageDown, &["]"]),
+    (Action::SelectNext, &["down", "j"]),
+    (Action::SelectPrevious, &["up", "k"]),
+    (Action::PageDown, &["pgdn"]),
+    (Action::PageUp, &["pgup"]),
+    (Action::SelectFirst, &["home"]),
+    (Action::SelectLast, &["end"]),
+    (Action::Details, &["enter"]),
+    (Action::Signal, &["x", "F9"]),
+    (Action::NextSortColumn, &["s"]),
+    (Action::PreviousSortColumn, &["S"]),
+    (Action::ReverseSort, &["r"]),
+    (Action::SortByCpu, &[]),
+    (Action::SortByMemory, &[]),
+    (Action::SortByTime, &[]),
+    (Action::Filter, &["/"]),
+    (Action::NextMatch, &["n"]),
+    (Action::PreviousMatch, &["N"]),
+    (Action::TreeView, &["v", "F5"]),
+    (Action::Collapse, &["left", "-"]),
+    (Action::Expand, &["right", "+"]),
+    (Action::ToggleCollapse, &["space"]),
+    (Action::UserSummary, &["U"]),
+];
+
+const VIM_KEYS: &[(Action, &[&str])] = &[
+    (Action::SelectFirst, &["home", "g"]),
+    (Action::SelectLast, &["end", "G"]),
+    (Action::PageDown, &["pgdn", "ctrl+f", "ctrl+d"]),
+    (Action::PageUp, &["pgup", "ctrl+b", "ctrl+u"]),
+    (Action::Collapse, &["left", "-", "h"]),
+    (Action::Expand, &["right", "+", "l"]),
+    (Action::NextLayout, &["L"]),
+    (Action::CpuGrouping, &["ctrl+g"]),
+];
+
+const HTOP_KEYS: &[(Action, &[&str])] = &[
+    (Action::Quit, &["q", "F10", "ctrl+c"]),
+    (Action::Help, &["?", "F1", "h"]),
+    (Action::NextTheme, &["F2"]),
+    (Action::PreviousTheme, &[]),
+    (Action::SelectNext, &["down"]),
+    (Action::SelectPrevious, &["up"]),
+    (Action::Signal, &["k", "x", "F9"]),
+    (Action::Filter, &["/", "F3", "F4"]),
+    (Action::TreeView, &["t", "F5"]),
+    (Action::NextSortColumn, &[">", "F6"]),
+    (Action::PreviousSortColumn, &["<"]),
+    (Action::ReverseSort, &["r", "I"]),
+    (Action::InterfaceList, &["tab"]),
+    (Action::SortByCpu, &["P"]),
+    (Action::SortByMemory, &["M"]),
+    (Action::SortByTime, &["T"]),
+];
+
+/// Resolved key bindings: the preset with the config overrides applied.
+pub struct Keymap {
+    pub preset: KeyPreset,
+    bindings: Vec<(KeyBinding, Action)>,
+}
+
+impl Keymap {
+    /// Builds the keymap and lists keys bound to more than one action.
+    /// Overridden actions come first, so their keys win over the preset.
+    pub fn new(config: &KeysConfig) -> (Keymap, Vec<String>) {
+        let overrides = match config.preset {
+            KeyPreset::Default => &[][..],
+            KeyPreset::Vim => VIM_KEYS,
+            KeyPreset::Htop => HTOP_KEYS,
+        };
+        let mut keys: BTreeMap<Action, Vec<KeyBinding>> = BTreeMap::new();
+        for (action, names) in DEFAULT_KEYS.iter().chain(overrides) {
+            // The preset tables are fixed and known to parse
+            let parsed = names.iter().filter_map(|name| name.parse().ok()).collect();
+            keys.insert(*action, parsed);
+        }
+
+        let mut bindings = vec![];
+        for (action, list) in &config.bindings {
+            bindings.extend(list.iter().map(|key| (*key, *action)));
+        }
+        for (action, list) in &keys {
+            if !config.bindings.contains_key(action) {
+                bindings.extend(list.iter().map(|key| (*key, *action)));
+            }
+        }
+
+        let mut conflicts = vec![];
+        for (i, (key, action)) in bindings.iter().enumerate() {
+            if let Some((_, first)) = bindings[..i].iter().find(|(k, a)| k == key && a != action) {
+                conflicts.push(format!("'{}' is bound to both {} and {}", key, first, action));
+            }
+        }
+        (Keymap { preset: config.preset, bindings }, conflicts)
+    }
+
+    pub fn action(&self, key: &KeyEvent) -> Option<Action> {
+        self.action_for(&KeyBinding::from(key))
+    }
+
+    fn action_for(&self, key: &KeyBinding) -> Option<Action> {
+        self.bindings.iter().find(|(k, _)| k == key).map(|(_, action)| *action)
+    }
+
+    /// Keys that trigger `action`, in binding order.
+    pub fn keys(&self, action: Action) -> Vec<KeyBinding> {
+        // A key shadowed by an earlier binding does not trigger `action`
+        let mut keys: Vec<KeyBinding> = self.bindings.iter().filter(|(_, a)| *a == action).map(|(k, _)| *k).collect();
+        keys.retain(|key| self.action_for(key) == Some(action));
+        keys.dedup();
+        keys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preset_keys_round_trip_through_display() {
+        for (action, names) in DEFAULT_KEYS.iter().chain(VIM_KEYS).chain(HTOP_KEYS) {
+            for name in *names {
+                let key: KeyBinding = name.parse().unwrap_or_else(|e| panic!("{} for {}: {}", name, action, e));
+                let shown = key.to_string();
+                assert_eq!(shown.parse::<KeyBinding>(), Ok(key), "'{}' is shown as '{}'", name, shown);
+            }
+        }
+    }
+
+    #[test]
+    fn presets_have_no_conflicts() {
+        for preset in [KeyPreset::Default, KeyPreset::Vim, KeyPreset::Htop] {
+            let config = KeysConfig { preset, bindings: BTreeMap::new() };
+            let (_, conflicts) = Keymap::new(&config);
+            assert!(conflicts.is_empty(), "{} preset: {:?}", preset.name(), conflicts);
+        }
+    }
+}
@@ -1,36 +1,106 @@
 mod app;
+mod cli;
+mod config;
+mod cpu;
+mod disk;
+mod filter;
+mod import;
+mod keymap;
+mod procinfo;
 mod theme;
+mod tree;
 mod tui;
 mod ui;
+mod units;
 
 use std::{error::Error, time::Duration};
-use crossterm::event::{self, Event, KeyCode};
-use app::App;
+use clap::Parser;
+use cli::{Cli, Command};
+use sysinfo::Pid;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Position;
+use app::{App, InputMode, Panel, SortColumn};
+use keymap::Action;
+use config::Config;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    let config_path = cli.config.clone().or_else(config::default_config_path);
+    let config = match config_path.as_deref().map(Config::load).transpose() {
+        Ok(config) => config.unwrap_or_default(),
+        Err(err) => {
+            eprintln!("xtop: {:#}", err);
+            std::process::exit(1);
+        }
+    };
+
+    if let Some(Command::ImportTheme { file, format, name, output, force }) = cli.command.as_ref() {
+        let Some(output) = output.clone().or_else(|| config.themes_dir()) else {
+            eprintln!("xtop: no themes directory found, pass --output");
+            std::process::exit(1);
+        };
+        if let Err(err) = import::import_theme(file, *format, name.clone(), &output, *force) {
+            eprintln!("xtop: {:#}", err);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let (themes, theme_errors) = theme::load_themes(config.themes_dir().as_deref());
+    let mut theme_names: Vec<String> = themes.keys().cloned().collect();
+    theme_names.sort();
+    if cli.list_themes {
+        for err in &theme_errors {
+            eprintln!("xtop: skipped theme {}", err);
+        }
+        for name in &theme_names {
+            println!("{}", name);
+        }
+        return Ok(());
+    }
+    cli.validate_theme(&theme_names);
+    let user_filter = cli.resolve_user();
+
     tui::install_panic_hook();
-    let mut terminal = tui::init()?;
-    let mut app = App::new();
-    let tick_rate = Duration::from_millis(1000); // 1 second update
+    let mut terminal = tui::init(!cli.no_mouse)?;
+    let mut app = App::new(config, config_path, themes);
+    // Command-line overrides only last for this run; they are not saved
+    if let Some(theme) = cli.theme {
+        app.set_theme(&theme);
+    }
+    if let Some(layout) = cli.layout {
+        app.layout_mode = layout;
+    }
+    if let Some(interval) = cli.interval {
+        app.tick_rate = Duration::from_millis(interval);
+    }
+    if !theme_errors.is_empty() {
+        app.append_status(format!("Skipped {} theme file(s): {}", theme_errors.len(), theme_errors.join("; ")));
+    }
+    app.watched_pids = cli.pids.into_iter().map(Pid::from).collect();
+    app.user_filter = user_filter;
+    if let Some(filter) = cli.filter {
+        app.filter.set_query(&filter);
+    }
+    app.refresh_process_list();
+    let tick_rate = app.tick_rate;
     let mut last_tick = std::time::Instant::now();
 
     loop {
-        terminal.draw(|f| ui::ui(f, &app))?;
+        terminal.draw(|f| ui::ui(f, &mut app))?;
 
         let timeout = tick_rate
             .checked_sub(last_tick.elapsed())
             .unwrap_or_else(|| Duration::from_secs(0));
 
         if crossterm::event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                match key.code {
-                    KeyCode::Char('q') => app.quit(),
-                    KeyCode::Char('t') => app.next_theme(),
-                    KeyCode::Char('T') => app.previous_theme(),
-                    KeyCode::Char('l') => app.next_layout(),
-                    _ => {}
-                }
+            match event::read()? {
+                Event::Key(key) => handle_key(&mut app, key),
+                Event::Mouse(mouse) => handle_mouse(&mut app, mouse),
+                // Repaint everything at the new size right away
+                Event::Resize(_, _) => terminal.clear()?,
+                _ => {}
             }
         }
 
@@ -45,5 +115,175 @@ async fn main() -> Result<(), Box<dyn Error>> {
     }
 
     tui::restore()?;
+
+    if app.config.save_on_exit {
+        if let Err(err) = app.save_config() {
+            eprintln!("xtop: {:#}", err);
+        }
+    }
     Ok(())
 }
+
+/// Resolves `key` through the keymap. Popups and the filter prompt handle
+/// a few fixed keys themselves and reuse the navigation actions.
+fn handle_key(app: &mut App, key: KeyEvent) {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    if app.input_mode == InputMode::Editing && !app.show_help {
+        match key.code {
+            KeyCode::Esc => return app.clear_filter(),
+            KeyCode::Enter => return app.finish_filter(),
+            KeyCode::Backspace => return app.filter_pop(),
+            KeyCode::Char('r') if ctrl => return app.toggle_filter_regex(),
+            KeyCode::Char('s') if ctrl => return app.toggle_filter_case(),
+            KeyCode::Char(c) if !ctrl && !key.modifiers.contains(KeyModifiers::ALT) => return app.filter_push(c),
+            _ => {}
+        }
+    }
+    let action = app.keymap.action(&key);
+
+    if app.show_help {
+        match action {
+            Some(Action::Cancel | Action::Help | Action::Quit) => app.toggle_help(),
+            Some(Action::SelectNext) => app.scroll_help(1),
+            Some(Action::SelectPrevious) => app.scroll_help(-1),
+            Some(Action::PageDown) => app.scroll_help(10),
+            Some(Action::PageUp) => app.scroll_help(-10),
+            Some(Action::SelectFirst) => app.scroll_help(isize::MIN),
+            Some(Action::SelectLast) => app.scroll_help(isize::MAX),
+            _ => {}
+        }
+    } else if app.signal_dialog.is_some() {
+        match (key.code, action) {
+            (KeyCode::Enter | KeyCode::Char('y'), _) => app.signal_dialog_accept(),
+            (KeyCode::Char('n'), _) | (_, Some(Action::Cancel | Action::Quit)) => app.close_signal_dialog(),
+            (_, Some(Action::SelectNext)) => app.signal_dialog_next(),
+            (_, Some(Action::SelectPrevious)) => app.signal_dialog_previous(),
+            _ => {}
+        }
+    } else if let Some(pid) = app.process_details.as_ref().map(|d| d.pid) {
+        match action {
+            Some(Action::Cancel | Action::Details | Action::Quit) => app.close_process_details(),
+            Some(Action::SelectNext) => app.scroll_process_details(1),
+            Some(Action::SelectPrevious) => app.scroll_process_details(-1),
+            Some(Action::PageDown) => app.scroll_process_details(10),
+            Some(Action::PageUp) => app.scroll_process_details(-10),
+            Some(Action::SelectFirst) => app.scroll_process_details(isize::MIN),
+            Some(Action::SelectLast) => app.scroll_process_details(isize::MAX),
+            Some(Action::Signal) => app.open_signal_dialog_for(pid),
+            Some(Action::Help) => app.toggle_help(),
+            _ => {}
+        }
+    } else if app.show_cpu_details {
+        match action {
+            Some(Action::Cancel | Action::CpuDetails | Action::Quit) => app.toggle_cpu_details(),
+            Some(Action::SelectNext) => app.scroll_cpu_details(1),
+            Some(Action::SelectPrevious) => app.scroll_cpu_details(-1),
+            Some(Action::PageDown) => app.scroll_cpu_details(10),
+            Some(Action::PageUp) => app.scroll_cpu_details(-10),
+            Some(Action::Help) => app.toggle_help(),
+            _ => {}
+        }
+    } else if app.input_mode == InputMode::Editing {
+        let allowed = [Action::Quit, Action::Help, Action::SelectNext, Action::SelectPrevious, Action::PageDown, Action::PageUp];
+        if let Some(action) = action.filter(|a| allowed.contains(a)) {
+            run_action(app, action);
+        }
+    } else if let Some(action) = action {
+        run_action(app, action);
+    }
+}
+
+fn run_action(app: &mut App, action: Action) {
+    match action {
+        Action::Quit => app.quit(),
+        Action::Help => app.toggle_help(),
+        Action::NextTheme => app.next_theme(),
+        Action::PreviousTheme => app.previous_theme(),
+        Action::NextLayout => app.next_layout(),
+        Action::Cancel if app.maximized_panel.is_some() => app.maximized_panel = None,
+        Action::Cancel => app.clear_filter(),
+        Action::CpuView => app.next_cpu_view(),
+        Action::CpuGrouping => app.next_cpu_grouping(),
+        Action::CpuDetails => app.toggle_cpu_details(),
+        Action::NextInterface => app.next_interface(),
+        Action::InterfaceList => app.toggle_interface_list(),
+        Action::VirtualInterfaces => app.toggle_virtual_interfaces(),
+        Action::DiskIo => app.toggle_disk_io(),
+        Action::StorageSort => app.toggle_storage_sort(),
+        Action::StorageUp => app.scroll_storage(-1),
+        Action::StorageDown => app.scroll_storage(1),
+        Action::SelectNext => app.select_next_process(),
+        Action::SelectPrevious => app.select_previous_process(),
+        Action::PageDown => app.page_down_processes(),
+        Action::PageUp => app.page_up_processes(),
+        Action::SelectFirst => app.select_first_process(),
+        Action::SelectLast => app.select_last_process(),
+        Action::Details => app.open_process_details(),
+        Action::Signal => app.open_signal_dialog(),
+        Action::NextSortColumn => app.next_sort_column(),
+        Action::PreviousSortColumn => app.previous_sort_column(),
+        Action::ReverseSort => app.toggle_sort_direction(),
+        Action::SortByCpu => app.sort_by(SortColumn::Cpu),
+        Action::SortByMemory => app.sort_by(SortColumn::Memory),
+        Action::SortByTime => app.sort_by(SortColumn::RunTime),
+        Action::Filter => app.start_filter(),
+        Action::NextMatch => app.select_next_match(),
+        Action::PreviousMatch => app.select_previous_match(),
+        Action::TreeView => app.toggle_tree_mode(),
+        Action::Collapse => app.collapse_selected(),
+        Action::Expand => app.expand_selected(),
+        Action::ToggleCollapse => app.toggle_collapse_selected(),
+        Action::UserSummary => app.toggle_user_summary(),
+    }
+}
+
+/// Rows moved per wheel notch in the process list.
+const WHEEL_STEP: isize = 3;
+
+fn handle_mouse(app: &mut App, mouse: MouseEvent) {
+    let position = Position::new(mouse.column, mouse.row);
+    let areas = &app.click_areas;
+    let in_storage = areas.panels.iter().any(|(panel, area)| *panel == Panel::Storage && area.contains(position));
+    let wheel = match mouse.kind {
+        MouseEventKind::ScrollDown => 1,
+        MouseEventKind::ScrollUp => -1,
+        MouseEventKind::Down(MouseButton::Left) => 0,
+        _ => return,
+    };
+
+    // Popups take the wheel and swallow clicks
+    if app.show_help {
+        app.scroll_help(wheel * WHEEL_STEP);
+        return;
+    }
+    if app.signal_dialog.is_some() {
+        return;
+    }
+    if app.process_details.is_some() {
+        app.scroll_process_details(wheel * WHEEL_STEP);
+        return;
+    }
+    if app.show_cpu_details {
+        app.scroll_cpu_details(wheel * WHEEL_STEP);
+        return;
+    }
+
+    if wheel != 0 {
+        if in_storage {
+            app.scroll_storage(wheel);
+        } else if areas.processes.contains(position) && !app.show_user_summary {
+            app.move_process_selection(wheel * WHEEL_STEP);
+        }
+        return;
+    }
+
+    if let Some((_, action)) = areas.hints.iter().find(|(area, _)| area.contains(position)) {
+        run_action(app, *action);
+    } else if let Some((column, _)) = areas.columns.iter().find(|(_, area)| area.contains(position)) {
+        app.sort_by(*column);
+    } else if areas.process_rows.contains(position) {
+        app.select_process_row((mouse.row - areas.process_rows.y) as usize);
+    } else if let Some((panel, _)) = areas.panels.iter().find(|(_, area)| area.contains(position)) {
+        app.toggle_maximized(*panel);
+    }
+}
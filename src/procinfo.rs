@@ -0,0 +1,92 @@
+use sysinfo::Pid;
+
+/// What `/proc/<pid>` adds on top of `sysinfo::Process`. Every part is
+/// `None` when it cannot be read (other platforms, permissions, exited).
+#[derive(Default)]
+pub struct ProcExtras {
+    /// Descriptor number and what it points to.
+    pub open_files: Option<Vec<(String, String)>>,
+    pub maps: Option<MapsSummary>,
+    pub cgroups: Option<Vec<String>>,
+    /// Limit name, soft value, hard value and units.
+    pub limits: Option<Vec<[String; 4]>>,
+}
+
+/// Summary of `/proc/<pid>/maps`.
+pub struct MapsSummary {
+    pub regions: usize,
+    /// Total size of all mappings, in bytes.
+    pub mapped: u64,
+    /// Distinct files mapped into the address space, sorted.
+    pub files: Vec<String>,
+}
+
+impl ProcExtras {
+    #[cfg(target_os = "linux")]
+    pub fn read(pid: Pid) -> ProcExtras {
+        let dir = std::path::PathBuf::from(format!("/proc/{}", pid));
+        let text = |name: &str| std::fs::read_to_string(dir.join(name)).ok();
+
+        let open_files = std::fs::read_dir(dir.join("fd")).ok().map(|entries| {
+            let mut fds: Vec<(String, String)> = entries
+                .filter_map(|e| e.ok())
+                .map(|e| {
+                    let target = std::fs::read_link(e.path()).map(|t| t.display().to_string()).unwrap_or_default();
+                    (e.file_name().to_string_lossy().into_owned(), target)
+                })
+                .collect();
+            fds.sort_by_key(|(fd, _)| fd.parse::<u64>().unwrap_or(u64::MAX));
+            fds
+        });
+
+        let maps = text("maps").map(|maps| {
+            let mut summary = MapsSummary { regions: 0, mapped: 0, files: vec![] };
+            for line in maps.lines() {
+                let mut fields = line.split_whitespace();
+                let Some((start, end)) = fields.next().and_then(|range| range.split_once('-')) else { continue };
+                let size = u64::from_str_radix(end, 16).unwrap_or(0).saturating_sub(u64::from_str_radix(start, 16).unwrap_or(0));
+                summary.regions += 1;
+                summary.mapped += size;
+                if let Some(path) = fields.nth(4).filter(|p| p.starts_with('/')) {
+                    summary.files.push(path.to_string());
+                }
+            }
+            summary.files.sort();
+            summary.files.dedup();
+            summary
+        });
+
+        let cgroups = text("cgroup").map(|cgroup| cgroup.lines().map(|l| l.to_string()).collect());
+
+        // Columns are fixed width; take their positions from the header
+        let limits = text("limits").and_then(|limits| {
+            let mut lines = limits.lines();
+            let header = lines.next()?;
+            let soft = header.find("Soft Limit")?;
+            let hard = header.find("Hard Limit")?;
+            let units = header.find("Units")?;
+            let column = |line: &str, from: usize, to: usize| {
+                line.get(from..to.min(line.len())).unwrap_or("").trim().to_string()
+            };
+            Some(
+                lines
+                    .map(|line| {
+                        [
+                            column(line, 0, soft),
+                            column(line, soft, hard),
+                            column(line, hard, units),
+                            column(line, units, line.len()),
+                        ]
+                    })
+                    .collect(),
+            )
+        });
+
+        ProcExtras { open_files, maps, cgroups, limits }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn read(_pid: Pid) -> ProcExtras {
+        ProcExtras::default()
+    }
+}
@@ -1,23 +1,55 @@
 use ratatui::style::Color;
+use serde_json::{Map, Value};
 use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
 
 #[derive(Clone, Debug)]
 pub struct Theme {
     pub name: String,
     pub palette: [Color; 16],
+    /// Optional semantic overrides; unset entries fall back to the palette.
+    pub background: Option<Color>,
+    pub foreground: Option<Color>,
+    pub border: Option<Color>,
+    pub highlight: Option<Color>,
+    pub graph: Option<Vec<Color>>,
 }
 
 impl Theme {
+    pub fn new(name: &str, palette: [Color; 16]) -> Theme {
+        Theme {
+            name: name.to_string(),
+            palette,
+            background: None,
+            foreground: None,
+            border: None,
+            highlight: None,
+            graph: None,
+        }
+    }
+
     pub fn bg(&self) -> Color {
-        self.palette[0]
+        self.background.unwrap_or(self.palette[0])
     }
     
     pub fn fg(&self) -> Color {
-        self.palette[7]
+        self.foreground.unwrap_or(self.palette[7])
+    }
+
+    pub fn border(&self) -> Color {
+        self.border.unwrap_or_else(|| self.fg())
+    }
+
+    /// Background of the selected row; `None` means reversed video.
+    pub fn highlight(&self) -> Option<Color> {
+        self.highlight
     }
     
-    #[allow(dead_code)]
     pub fn graph_colors(&self) -> Vec<Color> {
+        if let Some(graph) = self.graph.as_ref().filter(|g| !g.is_empty()) {
+            return graph.clone();
+        }
         vec![
             self.palette[1], // Red
             self.palette[2], // Green
@@ -29,12 +61,14 @@ impl Theme {
     }
 }
 
-fn hex_to_color(hex: &str) -> Color {
-    let hex = hex.trim_start_matches('#');
-    let r = u8::from_str_radix(&hex[0..2], 16).unwrap_or(0);
-    let g = u8::from_str_radix(&hex[2..4], 16).unwrap_or(0);
-    let b = u8::from_str_radix(&hex[4..6], 16).unwrap_or(0);
-    Color::Rgb(r, g, b)
+/// Parses `#rrggbb` (or `rrggbb`) into an RGB color.
+pub fn hex_to_color(hex: &str) -> Result<Color, String> {
+    let digits = hex.trim().trim_start_matches('#');
+    if digits.len() != 6 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("invalid color '{}', expected #rrggbb", hex));
+    }
+    let channel = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).map_err(|e| e.to_string());
+    Ok(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
 }
 
 pub fn get_themes() -> HashMap<String, Theme> {
@@ -99,14 +133,107 @@ pub fn get_themes() -> HashMap<String, Theme> {
         let mut palette = [Color::Reset; 16];
         for (i, hex) in colors.iter().enumerate() {
             if i < 16 {
-                palette[i] = hex_to_color(hex);
+                palette[i] = hex_to_color(hex).expect("built-in theme colors are valid");
             }
         }
-        themes.insert(name.to_string(), Theme {
-            name: name.to_string(),
-            palette,
-        });
+        themes.insert(name.to_string(), Theme::new(name, palette));
     }
 
     themes
 }
+
+/// Built-in themes merged with every `*.json` theme in `dir`. User themes
+/// replace built-ins of the same name. Files that fail to parse are skipped
+/// and reported in the returned error list.
+pub fn load_themes(dir: Option<&Path>) -> (HashMap<String, Theme>, Vec<String>) {
+    let mut themes = get_themes();
+    let mut errors = vec![];
+    let Some(dir) = dir else { return (themes, errors) };
+    let Ok(entries) = fs::read_dir(dir) else { return (themes, errors) };
+
+    let mut paths: Vec<_> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        match load_theme_file(&path) {
+            Ok(theme) => {
+                themes.insert(theme.name.clone(), theme);
+            }
+            Err(err) => errors.push(format!("{}: {}", path.display(), err)),
+        }
+    }
+    (themes, errors)
+}
+
+fn load_theme_file(path: &Path) -> Result<Theme, String> {
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let value: Value = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+    let Value::Object(map) = value else {
+        return Err("expected a JSON object".to_string());
+    };
+    let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+    parse_theme(&stem, &map)
+}
+
+/// Builds a theme from the `references.md` schema: `color0`..`color15`, plus
+/// optional `name`, `background`, `foreground`, `border`, `highlight` and a
+/// `graph` array of colors.
+fn parse_theme(default_name: &str, map: &Map<String, Value>) -> Result<Theme, String> {
+    let string_field = |key: &str| -> Result<Option<&str>, String> {
+        match map.get(key) {
+            None => Ok(None),
+            Some(Value::String(s)) => Ok(Some(s)),
+            Some(_) => Err(format!("'{}' must be a string", key)),
+        }
+    };
+    let color_field = |key: &str| -> Result<Option<Color>, String> {
+        string_field(key)?
+            .map(|hex| hex_to_color(hex).map_err(|e| format!("{}: {}", key, e)))
+            .transpose()
+    };
+
+    for key in map.keys() {
+        let known = matches!(key.as_str(), "name" | "background" | "foreground" | "border" | "highlight" | "graph")
+            || key.strip_prefix("color").and_then(|n| n.parse::<usize>().ok()).is_some_and(|n| n < 16);
+        if !known {
+            return Err(format!("unknown key '{}'", key));
+        }
+    }
+
+    let mut palette = [Color::Reset; 16];
+    for (i, slot) in palette.iter_mut().enumerate() {
+        let key = format!("color{}", i);
+        *slot = color_field(&key)?.ok_or_else(|| format!("missing '{}'", key))?;
+    }
+
+    let name = string_field("name")?.unwrap_or(default_name);
+    if name.is_empty() {
+        return Err("theme name is empty".to_string());
+    }
+
+    let graph = match map.get("graph") {
+        None => None,
+        Some(Value::Array(items)) => Some(
+            items
+                .iter()
+                .map(|item| match item {
+                    Value::String(hex) => hex_to_color(hex).map_err(|e| format!("graph: {}", e)),
+                    _ => Err("'graph' entries must be strings".to_string()),
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        Some(_) => return Err("'graph' must be an array of colors".to_string()),
+    };
+
+    Ok(Theme {
+        background: color_field("background")?,
+        foreground: color_field("foreground")?,
+        border: color_field("border")?,
+        highlight: color_field("highlight")?,
+        graph,
+        ..Theme::new(name, palette)
+    })
+}
@@ -0,0 +1,120 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use sysinfo::{Pid, Process};
+
+/// Per-row information for the process table in tree mode.
+pub struct TreeRow {
+    /// Tree-drawing glyphs printed before the process name.
+    pub prefix: String,
+    pub has_children: bool,
+    pub collapsed: bool,
+    /// CPU and memory of the process plus all of its descendants.
+    pub subtree_cpu: f32,
+    pub subtree_memory: u64,
+}
+
+/// Flattens the parent/child hierarchy of `visible` processes into display
+/// order. Children of collapsed PIDs are skipped but still counted in the
+/// subtree totals of their collapsed ancestor.
+pub fn build_tree<F>(
+    processes: &HashMap<Pid, Process>,
+    visible: &HashSet<Pid>,
+    collapsed: &HashSet<Pid>,
+    compare: F,
+) -> (Vec<Pid>, HashMap<Pid, TreeRow>)
+where
+    F: Fn(&Process, &Process) -> Ordering,
+{
+    let mut children: HashMap<Pid, Vec<&Process>> = HashMap::new();
+    let mut roots: Vec<&Process> = vec![];
+    for pid in visible {
+        let Some(process) = processes.get(pid) else { continue };
+        match process.parent().filter(|parent| visible.contains(parent) && parent != pid) {
+            Some(parent) => children.entry(parent).or_default().push(process),
+            None => roots.push(process),
+        }
+    }
+    roots.sort_by(|a, b| compare(a, b));
+    for list in children.values_mut() {
+        list.sort_by(|a, b| compare(a, b));
+    }
+
+    let mut walker = Walker {
+        children: &children,
+        collapsed,
+        order: vec![],
+        rows: HashMap::new(),
+        visited: HashSet::new(),
+    };
+    let root_count = roots.len();
+    for (i, root) in roots.into_iter().enumerate() {
+        walker.walk(root, String::new(), String::new(), i + 1 == root_count, true);
+    }
+    (walker.order, walker.rows)
+}
+
+struct Walker<'a> {
+    children: &'a HashMap<Pid, Vec<&'a Process>>,
+    collapsed: &'a HashSet<Pid>,
+    order: Vec<Pid>,
+    rows: HashMap<Pid, TreeRow>,
+    visited: HashSet<Pid>,
+}
+
+impl Walker<'_> {
+    /// Visits `process` and its descendants, returning the subtree CPU and memory.
+    /// `indent` is the glyph column inherited from the ancestors; `emit` is false
+    /// below a collapsed node so only the totals are accumulated.
+    fn walk(&mut self, process: &Process, indent: String, branch: String, last: bool, emit: bool) -> (f32, u64) {
+        let pid = process.pid();
+        if !self.visited.insert(pid) {
+            return (0.0, 0);
+        }
+
+        let kids = self.children.get(&pid).map(|k| k.as_slice()).unwrap_or(&[]);
+        let collapsed = self.collapsed.contains(&pid) && !kids.is_empty();
+        if emit {
+            self.order.push(pid);
+        }
+
+        let child_indent = if branch.is_empty() {
+            String::new()
+        } else if last {
+            format!("{}   ", indent)
+        } else {
+            format!("{}│  ", indent)
+        };
+
+        // Threads are already accounted for in their owning process
+        let (mut cpu, mut memory) = if process.thread_kind().is_some() {
+            (0.0, 0)
+        } else {
+            (process.cpu_usage(), process.memory())
+        };
+        for (i, child) in kids.iter().enumerate() {
+            let child_last = i + 1 == kids.len();
+            let child_branch = if child_last { "└─ " } else { "├─ " }.to_string();
+            let (c, m) = self.walk(child, child_indent.clone(), child_branch, child_last, emit && !collapsed);
+            cpu += c;
+            memory += m;
+        }
+
+        if emit {
+            let marker = if kids.is_empty() {
+                ""
+            } else if collapsed {
+                "[+] "
+            } else {
+                "[-] "
+            };
+            self.rows.insert(pid, TreeRow {
+                prefix: format!("{}{}{}", indent, branch, marker),
+                has_children: !kids.is_empty(),
+                collapsed,
+                subtree_cpu: cpu,
+                subtree_memory: memory,
+            });
+        }
+        (cpu, memory)
+    }
+}
@@ -9,8 +9,11 @@ use std::panic;
 
 pub type Tui = Terminal<CrosstermBackend<Stdout>>;
 
-pub fn init() -> io::Result<Tui> {
-    execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+pub fn init(mouse: bool) -> io::Result<Tui> {
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    if mouse {
+        execute!(io::stdout(), EnableMouseCapture)?;
+    }
     enable_raw_mode()?;
     Terminal::new(CrosstermBackend::new(io::stdout()))
 }
@@ -1,112 +1,263 @@
 use ratatui::{
+    layout::Flex,
     prelude::*,
-    widgets::{Block, Borders, Paragraph, Gauge, Wrap, Table, Row, Cell, Chart, Dataset, Axis, GraphType},
+    widgets::{Block, Borders, Paragraph, Gauge, Table, Row, Cell, Chart, Dataset, Axis, GraphType, Clear, List, ListItem, ListState, Sparkline},
     Frame,
 };
-use crate::app::{App, LayoutMode};
+use crate::app::{disk_usage, ClickAreas, process_user, user_name, App, CpuView, InputMode, ProcessHistory, LayoutMode, Panel, SortColumn, SIGNALS, thread_count};
+use crate::units;
 use sysinfo::{System, Process};
+use crate::keymap::{Action, Context};
+use chrono::{Local, TimeZone};
 
-pub fn ui(f: &mut Frame, app: &App) {
-    match app.layout_mode {
-        LayoutMode::Dashboard => render_dashboard(f, app),
-        LayoutMode::Vertical => render_vertical(f, app),
-        LayoutMode::ProcessFocus => render_process_focus(f, app),
+pub fn ui(f: &mut Frame, app: &mut App) {
+    app.click_areas = ClickAreas::default();
+    if f.area().width < MIN_WIDTH || f.area().height < MIN_HEIGHT {
+        render_too_small(f, app);
+        return;
+    }
+    let maximized = app.maximized_panel.filter(|p| !visible_panels(app, &[*p]).is_empty());
+    match (maximized, app.layout_mode) {
+        (Some(panel), _) => render_maximized(f, app, panel),
+        (None, LayoutMode::Dashboard) => render_dashboard(f, app),
+        (None, LayoutMode::Vertical) => render_vertical(f, app),
+        (None, LayoutMode::ProcessFocus) => render_process_focus(f, app),
+    }
+
+    if app.show_cpu_details {
+        render_cpu_details(f, app);
+    }
+    if app.process_details.is_some() {
+        render_process_details(f, app);
+    }
+    if app.signal_dialog.is_some() {
+        render_signal_dialog(f, app);
+    }
+    if app.show_help {
+        render_help(f, app);
+    }
+}
+
+/// Bordered panel block in the current theme colors.
+fn panel_block<'a>(app: &App, title: impl Into<Line<'a>>) -> Block<'a> {
+    Block::default().title(title).borders(Borders::ALL)
+        .border_style(Style::default().fg(app.current_theme.border()))
+        .style(Style::default().fg(app.current_theme.fg()).bg(app.current_theme.bg()))
+}
+
+/// Style of the selected row in tables and lists.
+fn highlight_style(app: &App) -> Style {
+    match app.current_theme.highlight() {
+        Some(color) => Style::default().bg(color).add_modifier(Modifier::BOLD),
+        None => Style::default().add_modifier(Modifier::REVERSED),
     }
 }
 
-fn render_dashboard(f: &mut Frame, app: &App) {
+/// First key bound to each of `actions`, joined with `separator`; `None`
+/// when none of them is bound.
+fn bound_keys(app: &App, actions: &[Action], separator: &str) -> Option<String> {
+    let keys: Vec<String> = actions.iter().filter_map(|a| app.keymap.keys(*a).first().map(|k| k.to_string())).collect();
+    (!keys.is_empty()).then(|| keys.join(separator))
+}
+
+/// Hints like `[Down/Up] Scroll [Esc] Close` from the current keymap,
+/// leaving out unbound actions.
+fn key_hints(app: &App, hints: &[(&[Action], &str)]) -> String {
+    hints
+        .iter()
+        .filter_map(|(actions, label)| Some(format!("[{}] {}", bound_keys(app, actions, "/")?, label)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Returns a rectangle of the given size centered inside `area`.
+fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+fn render_panel(f: &mut Frame, app: &mut App, panel: Panel, area: Rect) {
+    app.click_areas.panels.push((panel, area));
+    match panel {
+        Panel::Cpu => render_cpu(f, app, area),
+        Panel::Memory => render_memory(f, app, area),
+        Panel::Storage => render_storage(f, app, area),
+        Panel::Network => render_network(f, app, area),
+        Panel::Sensors => render_sensors(f, app, area),
+    }
+}
+
+/// Splits `area` evenly between `panels` along `direction` and renders them.
+fn render_panels(f: &mut Frame, app: &mut App, panels: &[Panel], direction: Direction, area: Rect) {
+    if panels.is_empty() {
+        return;
+    }
+    let count = panels.len() as u32;
+    let chunks = Layout::default()
+        .direction(direction)
+        .constraints(vec![Constraint::Ratio(1, count); panels.len()])
+        .split(area);
+    for (panel, chunk) in panels.iter().zip(chunks.iter()) {
+        render_panel(f, app, *panel, *chunk);
+    }
+}
+
+fn visible_panels(app: &App, panels: &[Panel]) -> Vec<Panel> {
+    panels
+        .iter()
+        .copied()
+        .filter(|p| app.config.panels.shows(*p))
+        .filter(|p| *p != Panel::Sensors || !app.components.is_empty())
+        .collect()
+}
+
+/// Below this size only a "terminal too small" notice is drawn.
+const MIN_WIDTH: u16 = 40;
+const MIN_HEIGHT: u16 = 12;
+const HEADER_HEIGHT: u16 = 3;
+/// Border, column headers and two rows.
+const MIN_PROCESS_HEIGHT: u16 = 6;
+const MIN_PANEL_HEIGHT: u16 = 5;
+const MIN_PANEL_WIDTH: u16 = 24;
+
+/// Order in which panels are kept when not all of them fit.
+const PANEL_PRIORITY: [Panel; 5] = [Panel::Cpu, Panel::Memory, Panel::Network, Panel::Storage, Panel::Sensors];
+
+/// Keeps the highest priority panels that get at least `min` cells each
+/// out of `space`, in their original order.
+fn fit_panels(panels: &[Panel], space: u16, min: u16) -> Vec<Panel> {
+    let fits = (space / min.max(1)) as usize;
+    let keep: Vec<Panel> = PANEL_PRIORITY.iter().copied().filter(|p| panels.contains(p)).take(fits).collect();
+    panels.iter().copied().filter(|p| keep.contains(p)).collect()
+}
+
+fn render_too_small(f: &mut Frame, app: &App) {
+    let area = f.area();
+    let style = Style::default().fg(app.current_theme.fg()).bg(app.current_theme.bg());
+    let text = vec![
+        Line::from(Span::styled("Terminal too small", style.add_modifier(Modifier::BOLD))),
+        Line::from(format!("{}x{}, need {}x{}", area.width, area.height, MIN_WIDTH, MIN_HEIGHT)),
+    ];
+    let y = area.y + area.height.saturating_sub(text.len() as u16) / 2;
+    let message = Rect { y, height: area.height.min(text.len() as u16), ..area };
+    f.render_widget(Block::default().style(style), area);
+    f.render_widget(Paragraph::new(text).style(style).alignment(Alignment::Center), message);
+}
+
+fn render_dashboard(f: &mut Frame, app: &mut App) {
+    let area = f.area();
+    let mut left = visible_panels(app, &[Panel::Cpu, Panel::Sensors]);
+    let mut right = visible_panels(app, &[Panel::Memory, Panel::Storage, Panel::Network]);
+
+    // The stats area takes 45% of the screen but never squeezes the process
+    // table below its minimum; panels that no longer fit are dropped.
+    let spare = area.height.saturating_sub(HEADER_HEIGHT + MIN_PROCESS_HEIGHT);
+    let stats_height = ((u32::from(area.height) * 45 / 100) as u16).min(spare);
+    if area.width < 2 * MIN_PANEL_WIDTH {
+        // Too narrow for two columns: stack everything on the left
+        left.append(&mut right);
+    }
+    let left = fit_panels(&left, stats_height, MIN_PANEL_HEIGHT);
+    let right = fit_panels(&right, stats_height, MIN_PANEL_HEIGHT);
+    let has_stats = !left.is_empty() || !right.is_empty();
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(3), // Header
-            Constraint::Percentage(45), // Top half (CPU, Mem, Net)
-            Constraint::Percentage(52), // Bottom half (Processes)
+            Constraint::Length(HEADER_HEIGHT),
+            Constraint::Length(if has_stats { stats_height } else { 0 }), // Top half (CPU, Mem, Net)
+            Constraint::Min(0), // Bottom half (Processes)
         ])
-        .split(f.area());
+        .split(area);
 
     render_header(f, app, chunks[0]);
     
     let top_chunks = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .constraints(match (left.is_empty(), right.is_empty()) {
+            (false, false) => [Constraint::Percentage(50), Constraint::Percentage(50)],
+            (true, _) => [Constraint::Length(0), Constraint::Percentage(100)],
+            (false, true) => [Constraint::Percentage(100), Constraint::Length(0)],
+        })
         .split(chunks[1]);
 
-    render_cpu(f, app, top_chunks[0]);
-    
-    let right_chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Percentage(33), // Memory
-            Constraint::Percentage(33), // Storage
-            Constraint::Percentage(34), // Network
-        ])
-        .split(top_chunks[1]);
-        
-    render_memory(f, app, right_chunks[0]);
-    render_storage(f, app, right_chunks[1]);
-    render_network(f, app, right_chunks[2]);
+    render_panels(f, app, &left, Direction::Vertical, top_chunks[0]);
+    render_panels(f, app, &right, Direction::Vertical, top_chunks[1]);
 
     render_processes(f, app, chunks[2]);
 }
 
-fn render_vertical(f: &mut Frame, app: &App) {
+fn render_vertical(f: &mut Frame, app: &mut App) {
+    let area = f.area();
+    let panels = visible_panels(app, &[Panel::Cpu, Panel::Memory, Panel::Storage, Panel::Network, Panel::Sensors]);
+
+    // Panels are 10 rows tall, shrinking (and then dropping out) so the
+    // process table keeps its minimum height
+    let spare = area.height.saturating_sub(HEADER_HEIGHT + MIN_PROCESS_HEIGHT);
+    let panels = fit_panels(&panels, spare, MIN_PANEL_HEIGHT);
+    let panel_height = (spare / panels.len().max(1) as u16).min(10);
+
+    let mut constraints = vec![Constraint::Length(HEADER_HEIGHT)];
+    constraints.extend(panels.iter().map(|_| Constraint::Length(panel_height)));
+    constraints.push(Constraint::Min(0)); // Processes
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3), // Header
-            Constraint::Length(10), // CPU
-            Constraint::Length(10), // Memory
-            Constraint::Length(10), // Storage
-            Constraint::Length(6),  // Network
-            Constraint::Min(0),     // Processes
-        ])
-        .split(f.area());
+        .constraints(constraints)
+        .split(area);
 
     render_header(f, app, chunks[0]);
-    render_cpu(f, app, chunks[1]);
-    render_memory(f, app, chunks[2]);
-    render_storage(f, app, chunks[3]);
-    render_network(f, app, chunks[4]);
-    render_processes(f, app, chunks[5]);
+    for (i, panel) in panels.iter().enumerate() {
+        render_panel(f, app, *panel, chunks[i + 1]);
+    }
+    render_processes(f, app, chunks[chunks.len() - 1]);
 }
 
-fn render_process_focus(f: &mut Frame, app: &App) {
+/// One panel filling everything below the header.
+fn render_maximized(f: &mut Frame, app: &mut App, panel: Panel) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3), // Header
-            Constraint::Length(10), // Quick Stats Row
-            Constraint::Min(0),     // Processes (Dominant)
-        ])
+        .constraints([Constraint::Length(HEADER_HEIGHT), Constraint::Min(0)])
         .split(f.area());
 
     render_header(f, app, chunks[0]);
+    render_panel(f, app, panel, chunks[1]);
+}
 
-    let stats_chunks = Layout::default()
-        .direction(Direction::Horizontal)
+fn render_process_focus(f: &mut Frame, app: &mut App) {
+    let area = f.area();
+    let panels = visible_panels(app, &[Panel::Cpu, Panel::Memory, Panel::Storage, Panel::Network, Panel::Sensors]);
+
+    let stats_height = area.height.saturating_sub(HEADER_HEIGHT + MIN_PROCESS_HEIGHT).min(10);
+    let panels = if stats_height < MIN_PANEL_HEIGHT {
+        vec![]
+    } else {
+        fit_panels(&panels, area.width, MIN_PANEL_WIDTH)
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
         .constraints([
-            Constraint::Percentage(25),
-            Constraint::Percentage(25),
-            Constraint::Percentage(25),
-            Constraint::Percentage(25),
+            Constraint::Length(HEADER_HEIGHT),
+            Constraint::Length(if panels.is_empty() { 0 } else { stats_height }), // Quick Stats Row
+            Constraint::Min(0),     // Processes (Dominant)
         ])
-        .split(chunks[1]);
-        
-    render_cpu(f, app, stats_chunks[0]);
-    render_memory(f, app, stats_chunks[1]);
-    render_storage(f, app, stats_chunks[2]);
-    render_network(f, app, stats_chunks[3]);
-    
+        .split(area);
+
+    render_header(f, app, chunks[0]);
+    render_panels(f, app, &panels, Direction::Horizontal, chunks[1]);
     render_processes(f, app, chunks[2]);
 }
 
-fn render_header(f: &mut Frame, app: &App, area: Rect) {
+fn render_header(f: &mut Frame, app: &mut App, area: Rect) {
     let load_avg = System::load_average();
     let uptime = System::uptime();
-    let days = uptime / 86400;
-    let hours = (uptime % 86400) / 3600;
-    let minutes = (uptime % 3600) / 60;
-    let seconds = uptime % 60;
 
     let mode_str = match app.layout_mode {
         LayoutMode::Dashboard => "Dashboard",
@@ -114,14 +265,62 @@ fn render_header(f: &mut Frame, app: &App, area: Rect) {
         LayoutMode::ProcessFocus => "Process Focus",
     };
 
-    let text = format!(
-        "xtop | Theme: {} | Layout: {} | Uptime: {}d {}h {}m {}s | Load: {:.2} {:.2} {:.2} | [q] Quit [t] Theme [l] Layout",
-        app.current_theme.name, mode_str, days, hours, minutes, seconds, load_avg.one, load_avg.five, load_avg.fifteen
+    let mut spans = vec![];
+    let critical = app.critical_sensors();
+    if !critical.is_empty() {
+        let readings: Vec<String> = critical.iter().map(|(label, temp)| format!("{} {:.0}°C", label, temp)).collect();
+        let alert = Style::default().fg(app.current_theme.bg()).bg(app.current_theme.palette[1]).add_modifier(Modifier::BOLD);
+        spans.push(Span::styled(format!(" CRITICAL: {} ", readings.join(", ")), alert));
+        spans.push(Span::raw(" "));
+    }
+
+    // Shorter labels on narrow terminals: drop theme and layout, then the
+    // hint descriptions
+    let inner = Rect { x: area.x + 1, y: area.y + 1, width: area.width.saturating_sub(2), height: area.height.saturating_sub(2) };
+    let full = format!(
+        "xtop | Theme: {} | Layout: {} | Uptime: {} | Load: {:.2} {:.2} {:.2} | ",
+        app.current_theme.name, mode_str, units::duration(uptime), load_avg.one, load_avg.five, load_avg.fifteen
     );
+    let compact = format!("Up {} | Load {:.2} {:.2} {:.2} | ", units::duration(uptime), load_avg.one, load_avg.five, load_avg.fifteen);
+    // Each hint shows the first key bound to its action; unbound ones are left out
+    let hints: Vec<(Action, String, String)> = [(Action::Quit, "Quit"), (Action::NextTheme, "Theme"), (Action::NextLayout, "Layout"), (Action::Help, "Help")]
+        .into_iter()
+        .filter_map(|(action, name)| {
+            let key = format!("[{}]", app.keymap.keys(action).first()?);
+            Some((action, format!("{} {}", key, name), key))
+        })
+        .collect();
+    let hints_width = hints.iter().map(|(_, label, _)| label.len() + 1).sum::<usize>();
+    let used = Line::from(spans.clone()).width();
+    let short_hints = used + compact.len() + hints_width > inner.width as usize;
+    let (text, short_hints) = if used + full.len() + hints_width <= inner.width as usize {
+        (full, false)
+    } else {
+        (compact, short_hints)
+    };
+    spans.push(Span::raw(text));
+
+    // Hints are clickable; remember where each one lands on screen
+    let mut x = inner.x + Line::from(spans.clone()).width() as u16;
+    for (i, (action, label, key)) in hints.into_iter().enumerate() {
+        let label = if short_hints { key } else { label };
+        let width = label.len() as u16;
+        if i > 0 {
+            spans.push(Span::raw(" "));
+            x += 1;
+        }
+        spans.push(Span::raw(label));
+        let hint = Rect { x, y: inner.y, width, height: 1 }.intersection(inner);
+        if !hint.is_empty() {
+            app.click_areas.hints.push((hint, action));
+        }
+        x = x.saturating_add(width);
+    }
 
-    let p = Paragraph::new(text)
+    let p = Paragraph::new(Line::from(spans))
         .style(Style::default().fg(app.current_theme.fg()).bg(app.current_theme.bg()))
-        .block(Block::default().borders(Borders::ALL).title("System Info"));
+        .block(Block::default().borders(Borders::ALL).title("System Info")
+            .border_style(Style::default().fg(app.current_theme.border())));
     f.render_widget(p, area);
 }
 
@@ -138,65 +337,219 @@ fn render_cpu(f: &mut Frame, app: &App, area: Rect) {
         }
     }
     
-    let title = if max_temp > 0.0 {
-        format!("CPU (Max: {:.1}°C)", max_temp)
-    } else {
-        "CPU".to_string()
-    };
+    let mut title = "CPU".to_string();
+    if let Some(&(_, mhz)) = app.cpu_freq_history.last().filter(|(_, mhz)| *mhz > 0.0) {
+        title.push_str(&format!(" {}", units::frequency(mhz)));
+    }
+    if max_temp > 0.0 {
+        title.push_str(&format!(" (Max: {:.1}°C)", max_temp));
+    }
 
-    let block = Block::default().title(title).borders(Borders::ALL)
-        .style(Style::default().fg(app.current_theme.fg()).bg(app.current_theme.bg()));
+    let block = panel_block(app, title);
     let inner_area = block.inner(area);
     f.render_widget(block, area);
 
     let cpus = app.sys.cpus();
     let count = cpus.len();
+    let graph_colors = app.current_theme.graph_colors();
     if count == 0 { return; }
 
-    let constraints = if inner_area.width > 40 {
-        vec![Constraint::Percentage(50), Constraint::Percentage(50)]
-    } else {
-        vec![Constraint::Percentage(100)]
-    };
-    
-    let cols = Layout::default().direction(Direction::Horizontal).constraints(constraints).split(inner_area);
-    
-    let items_per_col = (count + 1) / cols.len(); 
-    
-    for (col_idx, col_area) in cols.iter().enumerate() {
-        let start = col_idx * items_per_col;
-        let end = (start + items_per_col).min(count);
-        
-        let rows = Layout::default().direction(Direction::Vertical)
-            .constraints(vec![Constraint::Length(1); end - start])
-            .split(*col_area);
-            
-        for (i, row_area) in rows.iter().enumerate() {
-            let cpu_idx = start + i;
-            if cpu_idx >= count { break; }
-            
-            let cpu = &cpus[cpu_idx];
-            let usage = cpu.cpu_usage();
-            let label = format!("CPU{:<2} {:>3.0}%", cpu_idx, usage);
-            
+    // Breakdown line and aggregate chart on top when there is room for them
+    let show_breakdown = app.cpu_breakdown.is_some() && inner_area.height >= 4;
+    let show_chart = inner_area.height >= 8;
+    let chunks = Layout::default().direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(if show_breakdown { 1 } else { 0 }),
+            Constraint::Percentage(if show_chart { 40 } else { 0 }),
+            Constraint::Min(0),
+        ])
+        .split(inner_area);
+
+    if let Some(breakdown) = app.cpu_breakdown.filter(|_| show_breakdown) {
+        let faint = Style::default().fg(app.current_theme.palette[8]);
+        let value = |v: f64, color: Color| Span::styled(format!("{:>5.1}% ", v), Style::default().fg(color));
+        let line = Line::from(vec![
+            Span::styled("usr", faint), value(breakdown.user, app.current_theme.palette[2]),
+            Span::styled("sys", faint), value(breakdown.system, app.current_theme.palette[1]),
+            Span::styled("wait", faint), value(breakdown.iowait, app.current_theme.palette[3]),
+            Span::styled("steal", faint), value(breakdown.steal, app.current_theme.palette[5]),
+        ]);
+        f.render_widget(Paragraph::new(line), chunks[0]);
+    }
+
+    if show_chart {
+        let faint = Style::default().fg(app.current_theme.palette[8]);
+        let datasets = vec![
+            Dataset::default()
+                .name("CPU")
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(graph_colors[0]))
+                .data(&app.cpu_total_history),
+        ];
+        let total = app.sys.global_cpu_usage();
+        let chart = Chart::new(datasets)
+            .block(Block::default().borders(Borders::BOTTOM).border_style(faint)
+                .title(Span::styled(format!("Total {:.0}%", total), faint)))
+            .x_axis(Axis::default().bounds([app.tick_count - app.config.history_length as f64, app.tick_count]))
+            .y_axis(Axis::default().bounds([0.0, 100.0]))
+            .legend_position(None);
+        f.render_widget(chart, chunks[1]);
+    }
+    render_cpu_cores(f, app, chunks[2]);
+}
+
+/// Per-core usage in as many columns as the area allows, falling back to the
+/// heatmap when even that cannot fit every core.
+fn render_cpu_cores(f: &mut Frame, app: &App, area: Rect) {
+    let cpus = app.sys.cpus();
+    if area.height == 0 || area.width == 0 {
+        return;
+    }
+    let graph_colors = app.current_theme.graph_colors();
+    let groups = app.cpu_topology.groups(cpus.len(), app.cpu_grouping);
+
+    // Group headers (`None`) followed by their logical CPUs
+    let mut rows: Vec<(&str, Option<usize>)> = vec![];
+    for (label, members) in &groups {
+        if !label.is_empty() {
+            rows.push((label, None));
+        }
+        rows.extend(members.iter().map(|&i| ("", Some(i))));
+    }
+
+    let min_width = if app.cpu_view == CpuView::Sparklines { 20 } else { 14 };
+    let needed = rows.len().div_ceil(area.height as usize);
+    let max_cols = (area.width as usize / min_width).max(1);
+    if app.cpu_view == CpuView::Heatmap || needed > max_cols {
+        render_cpu_heatmap(f, app, area, &groups);
+        return;
+    }
+    let cols = needed.max(if area.width > 40 { 2 } else { 1 }).min(max_cols).min(rows.len().max(1));
+    let per_col = rows.len().div_ceil(cols).max(1);
+    let col_areas = Layout::default().direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Ratio(1, cols as u32); cols])
+        .split(area);
+    let digits = cpus.len().saturating_sub(1).to_string().len().max(2);
+    let faint = Style::default().fg(app.current_theme.palette[8]).add_modifier(Modifier::BOLD);
+
+    for (col_area, chunk) in col_areas.iter().zip(rows.chunks(per_col)) {
+        for (i, (label, cpu_idx)) in chunk.iter().enumerate() {
+            let row_area = Rect { y: col_area.y + i as u16, height: 1, ..*col_area };
+            let Some(cpu_idx) = *cpu_idx else {
+                f.render_widget(Paragraph::new(Span::styled(*label, faint)), row_area);
+                continue;
+            };
+
+            let usage = cpus[cpu_idx].cpu_usage();
+            let label = format!("CPU{:<w$} {:>3.0}%", cpu_idx, usage, w = digits);
+            let color = graph_colors[cpu_idx % graph_colors.len()];
+
+            if app.cpu_view == CpuView::Sparklines {
+                let parts = Layout::default().direction(Direction::Horizontal)
+                    .constraints([Constraint::Length(label.len() as u16 + 1), Constraint::Min(0)])
+                    .split(row_area);
+                f.render_widget(Paragraph::new(label), parts[0]);
+                let history = app.cpu_history.get(cpu_idx).map(|h| h.as_slice()).unwrap_or(&[]);
+                let width = parts[1].width as usize;
+                let data: Vec<u64> = history[history.len().saturating_sub(width)..]
+                    .iter()
+                    .map(|(_, v)| *v as u64)
+                    .collect();
+                let sparkline = Sparkline::default()
+                    .data(&data)
+                    .max(100)
+                    .style(Style::default().fg(color));
+                f.render_widget(sparkline, parts[1]);
+                continue;
+            }
+
             let gauge = Gauge::default()
-                .gauge_style(Style::default().fg(app.current_theme.palette[1 + (cpu_idx % 6)]).bg(app.current_theme.bg()))
-                .percent(usage as u16)
+                .gauge_style(Style::default().fg(color).bg(app.current_theme.bg()))
+                .percent(usage.clamp(0.0, 100.0) as u16)
                 .label(label);
-            f.render_widget(gauge, *row_area);
+            f.render_widget(gauge, row_area);
         }
     }
 }
 
+/// One colored cell per logical CPU. Cells shrink from two-digit
+/// percentages to single blocks, and group labels are dropped, until
+/// everything fits the area.
+fn render_cpu_heatmap(f: &mut Frame, app: &App, area: Rect, groups: &[(String, Vec<usize>)]) {
+    let cpus = app.sys.cpus();
+    let palette = &app.current_theme.palette;
+    let faint = Style::default().fg(palette[8]);
+    let width = area.width as usize;
+    let level = |usage: f32| match usage {
+        u if u >= 80.0 => palette[1],
+        u if u >= 50.0 => palette[3],
+        u if u >= 10.0 => palette[2],
+        _ => palette[8],
+    };
+
+    let layout = |wide: bool, labels: bool| -> Vec<Line> {
+        let cell_width = if wide { 3 } else { 1 };
+        let mut lines = vec![];
+        let mut line: Vec<Span> = vec![];
+        let mut used = 0;
+        for (label, members) in groups {
+            let label_width = if labels && !label.is_empty() { label.len() + 1 } else { 0 };
+            // Groups start on a fresh line unless they fit behind the previous one
+            let gap = if used > 0 { 1 } else { 0 };
+            if used > 0 && used + gap + label_width + members.len() * cell_width > width {
+                lines.push(Line::from(std::mem::take(&mut line)));
+                used = 0;
+            } else if used > 0 {
+                line.push(Span::raw(" "));
+                used += 1;
+            }
+            if label_width > 0 {
+                line.push(Span::styled(format!("{} ", label), faint));
+                used += label_width;
+            }
+            for &i in members {
+                if used > 0 && used + cell_width > width {
+                    lines.push(Line::from(std::mem::take(&mut line)));
+                    used = 0;
+                }
+                let usage = cpus[i].cpu_usage();
+                if wide {
+                    let style = Style::default().fg(app.current_theme.bg()).bg(level(usage));
+                    line.push(Span::styled(format!("{:>2}", usage.clamp(0.0, 99.0) as u32), style));
+                    line.push(Span::raw(" "));
+                } else {
+                    line.push(Span::styled("■", Style::default().fg(level(usage))));
+                }
+                used += cell_width;
+            }
+        }
+        if !line.is_empty() {
+            lines.push(Line::from(line));
+        }
+        lines
+    };
+
+    let height = area.height as usize;
+    let lines = [(true, true), (false, true), (false, false)]
+        .into_iter()
+        .map(|(wide, labels)| layout(wide, labels))
+        .find(|lines| lines.len() <= height)
+        .unwrap_or_else(|| layout(false, false));
+    f.render_widget(Paragraph::new(lines), area);
+}
+
 fn render_memory(f: &mut Frame, app: &App, area: Rect) {
-    let block = Block::default().title("Memory").borders(Borders::ALL)
-        .style(Style::default().fg(app.current_theme.fg()).bg(app.current_theme.bg()));
+    let block = panel_block(app, "Memory");
     let inner_area = block.inner(area);
     f.render_widget(block, area);
 
+    // One-line gauges when there is no room for the tall ones
+    let gauge_height = if inner_area.height >= 6 { 3 } else { 1 };
     let chunks = Layout::default().direction(Direction::Vertical)
-        .constraints([Constraint::Length(3), Constraint::Length(3), Constraint::Min(0)])
+        .constraints([Constraint::Length(gauge_height), Constraint::Length(gauge_height), Constraint::Min(0)])
         .split(inner_area);
+    let fits = |label: &String| label.len() <= inner_area.width as usize;
 
     // RAM Stats
     let total_mem = app.sys.total_memory();
@@ -206,12 +559,16 @@ fn render_memory(f: &mut Frame, app: &App, area: Rect) {
     
     let mem_pct = if total_mem > 0 { (used_mem as f64 / total_mem as f64 * 100.0) as u16 } else { 0 };
     
-    let mem_text = format!(
-        "RAM: Total: {:.1} GB | Used: {:.1} GB | Avail: {:.1} GB", 
-        total_mem as f64 / 1024.0 / 1024.0 / 1024.0,
-        used_mem as f64 / 1024.0 / 1024.0 / 1024.0,
-        available_mem as f64 / 1024.0 / 1024.0 / 1024.0
+    let units = &app.config.units;
+    let mut mem_text = format!(
+        "RAM: Total: {} | Used: {} | Avail: {}",
+        units.bytes(total_mem),
+        units.bytes(used_mem),
+        units.bytes(available_mem)
     );
+    if !fits(&mem_text) {
+        mem_text = format!("RAM {}/{}", units.bytes(used_mem), units.bytes(total_mem));
+    }
 
     let mem_gauge = Gauge::default()
         .gauge_style(Style::default().fg(app.current_theme.palette[2]).bg(app.current_theme.bg()))
@@ -225,12 +582,15 @@ fn render_memory(f: &mut Frame, app: &App, area: Rect) {
     let free_swap = app.sys.free_swap();
     let swap_pct = if total_swap > 0 { (used_swap as f64 / total_swap as f64 * 100.0) as u16 } else { 0 };
     
-    let swap_text = format!(
-        "SWP: Total: {:.1} GB | Used: {:.1} GB | Free: {:.1} GB", 
-        total_swap as f64 / 1024.0 / 1024.0 / 1024.0,
-        used_swap as f64 / 1024.0 / 1024.0 / 1024.0,
-        free_swap as f64 / 1024.0 / 1024.0 / 1024.0
+    let mut swap_text = format!(
+        "SWP: Total: {} | Used: {} | Free: {}",
+        units.bytes(total_swap),
+        units.bytes(used_swap),
+        units.bytes(free_swap)
     );
+    if !fits(&swap_text) {
+        swap_text = format!("SWP {}/{}", units.bytes(used_swap), units.bytes(total_swap));
+    }
 
     let swap_gauge = Gauge::default()
         .gauge_style(Style::default().fg(app.current_theme.palette[3]).bg(app.current_theme.bg()))
@@ -250,122 +610,982 @@ fn render_memory(f: &mut Frame, app: &App, area: Rect) {
     
     let chart = Chart::new(datasets)
         .block(Block::default().borders(Borders::TOP))
-        .x_axis(Axis::default().bounds([app.tick_count - 100.0, app.tick_count]))
+        .x_axis(Axis::default().bounds([app.tick_count - app.config.history_length as f64, app.tick_count]))
         .y_axis(Axis::default().bounds([0.0, 100.0]));
     f.render_widget(chart, chunks[2]);
 }
 
-fn render_storage(f: &mut Frame, app: &App, area: Rect) {
-    let block = Block::default().title("Storage").borders(Borders::ALL)
-        .style(Style::default().fg(app.current_theme.fg()).bg(app.current_theme.bg()));
+fn render_storage(f: &mut Frame, app: &mut App, area: Rect) {
+    let read = app.disk_read_history.last().map(|(_, v)| *v).unwrap_or(0.0);
+    let write = app.disk_write_history.last().map(|(_, v)| *v).unwrap_or(0.0);
+    let units = &app.config.units;
+    let title = format!("Storage  R {}  W {}", units.rate(read), units.rate(write));
+    let mut block = panel_block(app, title);
+
+    // Two rows per mount: a capacity gauge and a details line
+    let fits = (block.inner(area).height as usize / 2).max(1);
+    // Keep the stored offset in range so scrolling back responds at once
+    app.storage_scroll = app.storage_scroll.min(app.visible_disks().len().saturating_sub(fits));
+    let offset = app.storage_scroll;
+    let app = &*app;
+    let disks = app.visible_disks();
+    if disks.len() > fits && !app.show_disk_io {
+        let mut range = format!("{}-{}/{}", offset + 1, (offset + fits).min(disks.len()), disks.len());
+        if let Some(keys) = bound_keys(app, &[Action::StorageUp, Action::StorageDown], " ") {
+            range = format!("{} {}", range, keys);
+        }
+        block = block.title_bottom(Line::from(range).right_aligned());
+    }
     let inner_area = block.inner(area);
     f.render_widget(block, area);
 
-    // List all disks
-    // We need to calculate how many lines per disk to fit them all, or just list them.
-    let disk_count = app.disks.len();
-    if disk_count == 0 { return; }
+    if app.show_disk_io {
+        render_disk_io(f, app, inner_area);
+        return;
+    }
+    if disks.is_empty() {
+        f.render_widget(Paragraph::new(Span::styled("No mounts to show", Style::default().fg(app.current_theme.palette[8]))), inner_area);
+        return;
+    }
 
-    let constraints = vec![Constraint::Length(3); disk_count];
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints(constraints)
-        .split(inner_area);
-        
-    for (i, disk) in app.disks.iter().enumerate() {
-        if i >= chunks.len() { break; }
-        
-        let total_space = disk.total_space();
-        let available_space = disk.available_space();
-        let used_space = total_space - available_space;
-        let disk_pct = if total_space > 0 { (used_space as f64 / total_space as f64 * 100.0) as u16 } else { 0 };
-        
-        // "encima total uso y libre" -> We put stats in the label or above.
-        // Let's format it nicely: "/mnt/data  Total: 100G  Used: 50G  Free: 50G"
+    let faint = Style::default().fg(app.current_theme.palette[8]);
+    for (i, disk) in disks.iter().skip(offset).take(fits).enumerate() {
+        let y = inner_area.y + i as u16 * 2;
+        let gauge_area = Rect { y, height: 1, ..inner_area };
+        let details_area = Rect { y: y + 1, height: 1, ..inner_area };
+
+        let total = disk.total_space();
+        let used = total.saturating_sub(disk.available_space());
+        let usage = disk_usage(disk);
+        let color = match usage {
+            u if u >= 0.95 => app.current_theme.palette[1],
+            u if u >= 0.85 => app.current_theme.palette[3],
+            _ => app.current_theme.palette[4],
+        };
         let label = format!(
-            "{:?}  Tot: {:.0}G  Use: {:.0}G  Free: {:.0}G",
-            disk.mount_point(),
-            total_space as f64 / 1024.0 / 1024.0 / 1024.0,
-            used_space as f64 / 1024.0 / 1024.0 / 1024.0,
-            available_space as f64 / 1024.0 / 1024.0 / 1024.0
+            "{}  {} / {} ({:.0}%)",
+            disk.mount_point().display(),
+            units.bytes(used),
+            units.bytes(total),
+            usage * 100.0
         );
-
         let gauge = Gauge::default()
-            .gauge_style(Style::default().fg(app.current_theme.palette[4]).bg(app.current_theme.bg()))
-            .percent(disk_pct)
+            .gauge_style(Style::default().fg(color).bg(app.current_theme.bg()))
+            .ratio(usage.clamp(0.0, 1.0))
             .label(label);
-        f.render_widget(gauge, chunks[i]);
+        f.render_widget(gauge, gauge_area);
+
+        let mut details = vec![
+            disk.name().to_string_lossy().into_owned(),
+            disk.file_system().to_string_lossy().into_owned(),
+            format!("{} free", units.bytes(disk.available_space())),
+        ];
+        if let Some((used, total)) = app.inodes.get(disk.mount_point()) {
+            details.push(format!("inodes {:.0}%", *used as f64 / *total as f64 * 100.0));
+        }
+        if disk.is_read_only() {
+            details.push("read-only".to_string());
+        }
+        if disk.is_removable() {
+            details.push("removable".to_string());
+        }
+        f.render_widget(Paragraph::new(Span::styled(format!("  {}", details.join("  ")), faint)), details_area);
+    }
+}
+
+/// Per-device throughput, IOPS and busy time above a read/write history chart.
+fn render_disk_io(f: &mut Frame, app: &App, area: Rect) {
+    let units = &app.config.units;
+    let faint = Style::default().fg(app.current_theme.palette[8]);
+    let read_color = app.current_theme.palette[4];
+    let write_color = app.current_theme.palette[5];
+    let height = area.height as usize;
+    let show_chart = height >= 6;
+    let table_height = if show_chart { (app.disk_io.len() + 1).min(height / 2) } else { height };
+    let chunks = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Length(table_height as u16), Constraint::Min(0)])
+        .split(area);
+
+    let optional = |v: Option<f64>, suffix: &str| v.map(|v| format!("{:.0}{}", v, suffix)).unwrap_or_else(|| "-".to_string());
+    let rows: Vec<Row> = app.disk_io.iter().map(|(name, rates)| {
+        Row::new(vec![
+            Cell::from(name.clone()),
+            Cell::from(units.rate(rates.read_bytes)).style(Style::default().fg(read_color)),
+            Cell::from(units.rate(rates.write_bytes)).style(Style::default().fg(write_color)),
+            Cell::from(optional(rates.read_iops, "")),
+            Cell::from(optional(rates.write_iops, "")),
+            Cell::from(optional(rates.busy, "%")),
+        ])
+    }).collect();
+    let header = Row::new(vec!["Device", "Read/s", "Write/s", "r IOPS", "w IOPS", "Busy"])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let widths = [
+        Constraint::Min(8), Constraint::Length(11), Constraint::Length(11),
+        Constraint::Length(7), Constraint::Length(7), Constraint::Length(5),
+    ];
+    f.render_widget(Table::new(rows, widths).header(header), chunks[0]);
+
+    if !show_chart {
+        return;
+    }
+    let max_rate = app.disk_read_history.iter().chain(app.disk_write_history.iter())
+        .map(|(_, rate)| *rate)
+        .fold(0.0, f64::max)
+        .max(1024.0);
+    let datasets = vec![
+        Dataset::default()
+            .name("Read")
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(read_color))
+            .data(&app.disk_read_history),
+        Dataset::default()
+            .name("Write")
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(write_color))
+            .data(&app.disk_write_history),
+    ];
+    let chart = Chart::new(datasets)
+        .block(Block::default().borders(Borders::TOP).border_style(faint))
+        .x_axis(Axis::default().bounds([app.tick_count - app.config.history_length as f64, app.tick_count]))
+        .y_axis(
+            Axis::default()
+                .bounds([0.0, max_rate * 1.1])
+                .labels([Span::styled("0", faint), Span::styled(units.rate(max_rate), faint)])
+                .style(faint),
+        )
+        .legend_position(None);
+    f.render_widget(chart, chunks[1]);
+}
+
+/// Color for a temperature reading by how close it is to the critical threshold.
+fn temperature_color(app: &App, temp: f32, critical: Option<f32>) -> Color {
+    let palette = &app.current_theme.palette;
+    match critical.filter(|c| *c > 0.0).map(|c| temp / c) {
+        Some(r) if r >= 0.9 => palette[1],
+        Some(r) if r >= 0.75 => palette[3],
+        Some(_) => palette[2],
+        None => app.current_theme.fg(),
+    }
+}
+
+fn render_sensors(f: &mut Frame, app: &App, area: Rect) {
+    let block = panel_block(app, "Sensors");
+    let inner_area = block.inner(area);
+    f.render_widget(block, area);
+    if inner_area.height == 0 {
+        return;
+    }
+
+    let graph_colors = app.current_theme.graph_colors();
+    let count = app.components.len();
+    let height = inner_area.height as usize;
+    // The table takes what it needs, leaving at least 40% for the chart
+    let show_chart = height >= 8;
+    let table_height = if show_chart { (count + 1).min(height - height * 2 / 5) } else { height };
+    let chunks = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Length(table_height as u16), Constraint::Min(0)])
+        .split(inner_area);
+
+    let reading = |v: Option<f32>| v.filter(|v| v.is_finite()).map(|v| format!("{:.1}°C", v)).unwrap_or_else(|| "-".to_string());
+    let rows: Vec<Row> = app.components.iter().enumerate().map(|(i, c)| {
+        let color = c.temperature().map(|t| temperature_color(app, t, c.critical())).unwrap_or(app.current_theme.fg());
+        let mut style = Style::default().fg(color);
+        if c.temperature().zip(c.critical()).is_some_and(|(t, crit)| crit > 0.0 && t >= crit) {
+            style = style.add_modifier(Modifier::BOLD | Modifier::REVERSED);
+        }
+        let temp = Cell::from(reading(c.temperature())).style(style);
+        Row::new(vec![
+            Cell::from(c.label().to_string()).style(Style::default().fg(graph_colors[i % graph_colors.len()])),
+            temp,
+            Cell::from(reading(c.max())),
+            Cell::from(reading(c.critical())),
+        ])
+    }).collect();
+    let header = Row::new(vec!["Sensor", "Temp", "Max", "Crit"]).style(Style::default().add_modifier(Modifier::BOLD));
+    let widths = [Constraint::Min(10), Constraint::Length(8), Constraint::Length(8), Constraint::Length(8)];
+    f.render_widget(Table::new(rows, widths).header(header), chunks[0]);
+
+    if show_chart {
+        let observed = app.sensor_history.iter().flatten().map(|(_, v)| *v).fold(0.0, f64::max);
+        let critical = app.components.iter().filter_map(|c| c.critical()).fold(0.0, f32::max) as f64;
+        let top = (observed.max(critical) / 10.0).ceil() * 10.0 + 10.0;
+        let datasets: Vec<Dataset> = app.sensor_history.iter().enumerate().map(|(i, history)| {
+            Dataset::default()
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(graph_colors[i % graph_colors.len()]))
+                .data(history)
+        }).collect();
+        let faint = Style::default().fg(app.current_theme.palette[8]);
+        let chart = Chart::new(datasets)
+            .block(Block::default().borders(Borders::TOP).border_style(faint))
+            .x_axis(Axis::default().bounds([app.tick_count - app.config.history_length as f64, app.tick_count]))
+            .y_axis(Axis::default().bounds([0.0, top]).style(faint)
+                .labels(vec![Span::raw("0"), Span::raw(format!("{:.0}°C", top))]))
+            .legend_position(None);
+        f.render_widget(chart, chunks[1]);
     }
 }
 
 fn render_network(f: &mut Frame, app: &App, area: Rect) {
-     let block = Block::default().title("Network").borders(Borders::ALL)
-        .style(Style::default().fg(app.current_theme.fg()).bg(app.current_theme.bg()));
+    let units = &app.config.units;
+    let title = match &app.selected_interface {
+        Some(name) => format!("Network ({})", name),
+        None => "Network".to_string(),
+    };
+    let block = panel_block(app, title);
     let inner_area = block.inner(area);
     f.render_widget(block, area);
-    
+
+    let chunks = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Min(0)])
+        .split(inner_area);
+
+    let visible = app.visible_interfaces();
+    let selected = app.selected_interface.as_ref().and_then(|name| app.interfaces.get(name).map(|stats| (name, stats)));
+    let (rx_rate, tx_rate, rx_peak, tx_peak, rx_history, tx_history) = match selected {
+        Some((_, stats)) => (stats.rx_rate, stats.tx_rate, stats.rx_peak, stats.tx_peak, &stats.rx_history, &stats.tx_history),
+        None => (app.net_rx_rate, app.net_tx_rate, app.net_rx_peak, app.net_tx_peak, &app.net_rx_history, &app.net_tx_history),
+    };
+
     let mut total_rx = 0;
     let mut total_tx = 0;
-    for (_, network) in &app.networks {
-        total_rx += network.received();
-        total_tx += network.transmitted();
+    for (name, network) in &app.networks {
+        let counted = match selected {
+            Some((selected_name, _)) => name == selected_name,
+            None => visible.contains(name),
+        };
+        if counted {
+            total_rx += network.total_received();
+            total_tx += network.total_transmitted();
+        }
     }
-    
+
+    let rx_color = app.current_theme.palette[4];
+    let tx_color = app.current_theme.palette[5];
+    let faint = Style::default().fg(app.current_theme.palette[8]);
     let text = vec![
         Line::from(vec![
-            Span::styled("Total RX: ", Style::default().fg(app.current_theme.fg())),
-            Span::styled(format!("{:.2} MB", total_rx as f64 / 1024.0 / 1024.0), Style::default().fg(app.current_theme.palette[4])),
+            Span::styled("RX ", Style::default().fg(app.current_theme.fg())),
+            Span::styled(format!("{:>12}", units.network_rate(rx_rate)), Style::default().fg(rx_color).add_modifier(Modifier::BOLD)),
+            Span::styled(format!("  peak {}  total {}", units.network_rate(rx_peak), units.bytes(total_rx)), faint),
         ]),
         Line::from(vec![
-            Span::styled("Total TX: ", Style::default().fg(app.current_theme.fg())),
-            Span::styled(format!("{:.2} MB", total_tx as f64 / 1024.0 / 1024.0), Style::default().fg(app.current_theme.palette[5])),
+            Span::styled("TX ", Style::default().fg(app.current_theme.fg())),
+            Span::styled(format!("{:>12}", units.network_rate(tx_rate)), Style::default().fg(tx_color).add_modifier(Modifier::BOLD)),
+            Span::styled(format!("  peak {}  total {}", units.network_rate(tx_peak), units.bytes(total_tx)), faint),
         ]),
     ];
-    
-    let p = Paragraph::new(text).wrap(Wrap { trim: true });
-    f.render_widget(p, inner_area);
+    f.render_widget(Paragraph::new(text), chunks[0]);
+
+    if app.show_interface_list {
+        render_interface_list(f, app, &visible, chunks[1]);
+        return;
+    }
+
+    // Auto-scale to the busiest sample currently on screen
+    let max_rate = rx_history.iter().chain(tx_history.iter())
+        .map(|(_, rate)| *rate)
+        .fold(0.0, f64::max)
+        .max(1024.0);
+
+    let datasets = vec![
+        Dataset::default()
+            .name("RX")
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(rx_color))
+            .data(rx_history),
+        Dataset::default()
+            .name("TX")
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(tx_color))
+            .data(tx_history),
+    ];
+
+    let chart = Chart::new(datasets)
+        .block(Block::default().borders(Borders::TOP).border_style(faint))
+        .x_axis(Axis::default().bounds([app.tick_count - app.config.history_length as f64, app.tick_count]))
+        .y_axis(
+            Axis::default()
+                .bounds([0.0, max_rate * 1.1])
+                .labels([Span::styled("0", faint), Span::styled(units.network_rate(max_rate), faint)])
+                .style(faint),
+        )
+        .legend_position(None);
+    f.render_widget(chart, chunks[1]);
 }
 
-fn render_processes(f: &mut Frame, app: &App, area: Rect) {
-    let block = Block::default().title("Processes").borders(Borders::ALL)
-        .style(Style::default().fg(app.current_theme.fg()).bg(app.current_theme.bg()));
+fn render_interface_list(f: &mut Frame, app: &App, names: &[String], area: Rect) {
+    let units = &app.config.units;
+    let rows: Vec<Row> = names.iter().filter_map(|name| {
+        let data = app.networks.get(name)?;
+        let stats = app.interfaces.get(name)?;
+        let mut ips: Vec<String> = data.ip_networks().iter()
+            .map(|ip| format!("{}/{}", ip.addr, ip.prefix))
+            .collect();
+        ips.sort();
+        let style = if app.selected_interface.as_ref() == Some(name) {
+            highlight_style(app)
+        } else {
+            Style::default()
+        };
+        Some(Row::new(vec![
+            name.clone(),
+            units.network_rate(stats.rx_rate),
+            units.network_rate(stats.tx_rate),
+            units.bytes(data.total_received()),
+            units.bytes(data.total_transmitted()),
+            format!("{}/{}", data.total_packets_received(), data.total_packets_transmitted()),
+            format!("{}/{}", data.total_errors_on_received(), data.total_errors_on_transmitted()),
+            data.mac_address().to_string(),
+            ips.join(", "),
+        ]).style(style))
+    }).collect();
+
+    let widths = [
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(9),
+        Constraint::Length(9),
+        Constraint::Length(15),
+        Constraint::Length(7),
+        Constraint::Length(17),
+        Constraint::Min(10),
+    ];
+    let table = Table::new(rows, widths)
+        .header(
+            Row::new(vec!["Iface", "RX/s", "TX/s", "RX tot", "TX tot", "Pkts rx/tx", "Err", "MAC", "IP"])
+                .style(Style::default().fg(app.current_theme.palette[6]).add_modifier(Modifier::BOLD)),
+        )
+        .block(Block::default().borders(Borders::TOP).border_style(Style::default().fg(app.current_theme.palette[8])));
+    f.render_widget(table, area);
+}
+
+fn render_processes(f: &mut Frame, app: &mut App, area: Rect) {
+    let mut title = if app.filter.is_active() {
+        format!("Processes ({} matching)", app.filter_match_count)
+    } else {
+        "Processes".to_string()
+    };
+    if app.tree_mode {
+        title.push_str(" [tree]");
+    }
+    if app.show_user_summary {
+        title.push_str(" by user");
+    }
+    let mut block = panel_block(app, title);
+    if app.input_mode == InputMode::Editing || app.filter.is_active() {
+        block = block.title_bottom(filter_prompt(app));
+    }
+    if let Some(status) = app.status() {
+        block = block.title_bottom(
+            Line::from(Span::styled(format!(" {} ", status), Style::default().fg(app.current_theme.palette[3])))
+                .right_aligned(),
+        );
+    }
     let inner_area = block.inner(area);
     f.render_widget(block, area);
+    app.click_areas.processes = area;
 
-    let mut procs: Vec<&Process> = app.sys.processes().values().collect();
-    procs.sort_by(|a, b| b.cpu_usage().partial_cmp(&a.cpu_usage()).unwrap_or(std::cmp::Ordering::Equal));
-    
-    let procs = procs.iter().take(50);
+    // Header row plus its bottom margin take two lines
+    app.process_page_size = inner_area.height.saturating_sub(2).max(1) as usize;
+
+    if app.show_user_summary {
+        render_user_summary(f, app, inner_area);
+        return;
+    }
+
+    let processes = app.sys.processes();
+    let procs = app.process_list.iter().filter_map(|pid| processes.get(pid));
     
     // Faint line separator
     let separator = Span::styled(" | ", Style::default().fg(app.current_theme.palette[8])); // Assuming palette[8] is faint/gray
 
+    let columns = &fitting_columns(&app.config.processes.columns, inner_area.width);
+    let last = columns.len().saturating_sub(1);
     let rows: Vec<Row> = procs.map(|p| {
-        Row::new(vec![
-            Cell::from(Line::from(vec![Span::raw(p.pid().to_string()), separator.clone()])),
-            Cell::from(Line::from(vec![Span::raw(p.name().to_string_lossy().into_owned()), separator.clone()])), 
-            Cell::from(Line::from(vec![Span::raw(format!("{:.1}%", p.cpu_usage())), separator.clone()])),
-            Cell::from(Line::from(vec![Span::raw(format!("{:.1} MB", p.memory() as f64 / 1024.0 / 1024.0)), separator.clone()])),
-            Cell::from(p.user_id().map(|u| u.to_string()).unwrap_or_else(|| "?".to_string())),
-        ])
+        Row::new(columns.iter().enumerate().map(|(i, column)| {
+            let text = match (app.tree_rows.get(&p.pid()), column) {
+                (Some(row), SortColumn::Name) => format!("{}{}", row.prefix, process_cell_text(app, p, *column)),
+                (Some(row), SortColumn::Cpu) if row.collapsed => format!("Σ{:.1}%", row.subtree_cpu),
+                (Some(row), SortColumn::Memory) if row.collapsed => {
+                    format!("Σ{}", app.config.units.bytes(row.subtree_memory))
+                }
+                _ => process_cell_text(app, p, *column),
+            };
+            if i == last {
+                Cell::from(text)
+            } else {
+                Cell::from(Line::from(vec![Span::raw(text), separator.clone()]))
+            }
+        }))
         .style(Style::default().fg(app.current_theme.fg()))
     }).collect();
 
-    let widths = [
-        Constraint::Length(10), // Increased slightly for separator
-        Constraint::Percentage(40),
-        Constraint::Length(12),
-        Constraint::Length(17),
-        Constraint::Length(10),
-    ];
+    let widths: Vec<Constraint> = columns.iter().map(|c| column_width(*c)).collect();
+    // Same split the table does internally (no highlight symbol, one cell of spacing)
+    let column_areas = Layout::horizontal(widths.clone()).flex(Flex::Start).spacing(1).split(inner_area);
+    app.click_areas.columns = columns
+        .iter()
+        .zip(column_areas.iter())
+        .map(|(column, area)| (*column, Rect { height: 1.min(area.height), ..*area }))
+        .collect();
+    app.click_areas.process_rows = Rect {
+        y: inner_area.y + 2.min(inner_area.height),
+        height: inner_area.height.saturating_sub(2),
+        ..inner_area
+    };
+
+    let arrow = if app.sort_descending { "▼" } else { "▲" };
+    let header_cells = columns.iter().enumerate().map(|(i, column)| {
+        let mut title = column.title().to_string();
+        if *column == app.sort_column {
+            title = format!("{}{}", title, arrow);
+        }
+        if i != last {
+            title.push_str(" |");
+        }
+        let style = if *column == app.sort_column {
+            Style::default().fg(app.current_theme.palette[3]).add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+        } else {
+            Style::default()
+        };
+        Cell::from(title).style(style)
+    });
 
     let table = Table::new(rows, widths)
         .header(
-            Row::new(vec!["PID |", "Name |", "CPU% |", "Mem |", "User"])
+            Row::new(header_cells)
                 .style(Style::default().fg(app.current_theme.palette[6]).add_modifier(Modifier::BOLD))
                 .bottom_margin(1)
         )
-        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        .row_highlight_style(highlight_style(app));
         
-    f.render_widget(table, inner_area);
+    f.render_stateful_widget(table, inner_area, &mut app.process_table_state);
+}
+
+fn filter_prompt(app: &App) -> Line<'static> {
+    let editing = app.input_mode == InputMode::Editing;
+    let mut spans = vec![
+        Span::styled(" /", Style::default().fg(app.current_theme.palette[6]).add_modifier(Modifier::BOLD)),
+        Span::raw(app.filter.query.clone()),
+    ];
+    if editing {
+        spans.push(Span::styled("█", Style::default().fg(app.current_theme.palette[6])));
+    }
+    let mode = format!(
+        " [{}] [{}]",
+        if app.filter.regex { "regex" } else { "text" },
+        if app.filter.case_insensitive { "Aa" } else { "Case" },
+    );
+    spans.push(Span::styled(mode, Style::default().fg(app.current_theme.palette[8])));
+    if let Some(err) = &app.filter.error {
+        spans.push(Span::styled(format!(" {}", err), Style::default().fg(app.current_theme.palette[1])));
+    }
+    if editing {
+        spans.push(Span::styled(" ^R regex ^S case [Enter] Keep [Esc] Clear ", Style::default().fg(app.current_theme.palette[8])));
+    } else {
+        spans.push(Span::raw(" "));
+    }
+    Line::from(spans)
+}
+
+fn column_width(column: SortColumn) -> Constraint {
+    match column {
+        SortColumn::Pid => Constraint::Length(10), // Increased slightly for separator
+        SortColumn::Name => Constraint::Min(16),
+        SortColumn::Cpu => Constraint::Length(10),
+        SortColumn::Memory => Constraint::Length(13),
+        SortColumn::User => Constraint::Length(14),
+        SortColumn::StartTime => Constraint::Length(9),
+        SortColumn::RunTime => Constraint::Length(13),
+        SortColumn::DiskRead | SortColumn::DiskWrite => Constraint::Length(12),
+        SortColumn::Threads => Constraint::Length(6),
+    }
+}
+
+/// The configured columns that fit in `width`, dropping from the right.
+/// The first column is always kept.
+fn fitting_columns(columns: &[SortColumn], width: u16) -> Vec<SortColumn> {
+    let mut used = 0;
+    columns
+        .iter()
+        .copied()
+        .enumerate()
+        .take_while(|(i, column)| {
+            let (Constraint::Length(w) | Constraint::Min(w)) = column_width(*column) else { return true };
+            used += w + u16::from(*i > 0);
+            *i == 0 || used <= width
+        })
+        .map(|(_, column)| column)
+        .collect()
+}
+
+fn process_cell_text(app: &App, p: &Process, column: SortColumn) -> String {
+    let units = &app.config.units;
+    match column {
+        SortColumn::Pid => p.pid().to_string(),
+        SortColumn::Name => p.name().to_string_lossy().into_owned(),
+        SortColumn::Cpu => format!("{:.1}%", p.cpu_usage()),
+        SortColumn::Memory => units.bytes(p.memory()),
+        SortColumn::User => process_user(&app.users, p),
+        SortColumn::StartTime => Local
+            .timestamp_opt(p.start_time() as i64, 0)
+            .single()
+            .map(|t| t.format("%H:%M").to_string())
+            .unwrap_or_else(|| "?".to_string()),
+        SortColumn::RunTime => units::duration(p.run_time()),
+        SortColumn::DiskRead => units.rate(p.disk_usage().read_bytes as f64 / app.refresh_elapsed),
+        SortColumn::DiskWrite => units.rate(p.disk_usage().written_bytes as f64 / app.refresh_elapsed),
+        SortColumn::Threads => thread_count(p).map(|n| n.to_string()).unwrap_or_else(|| "-".to_string()),
+    }
+}
+
+/// Model, core counts, frequency limits and governors, with a per-core table.
+fn render_cpu_details(f: &mut Frame, app: &App) {
+    let cpus = app.sys.cpus();
+    let area = f.area();
+    let area = centered_rect(96, (cpus.len() as u16 + 16).max(20).min(area.height.saturating_sub(2)), area);
+    f.render_widget(Clear, area);
+
+    let block = Block::default().title("CPU Details").borders(Borders::ALL)
+        .border_style(Style::default().fg(app.current_theme.border()))
+        .style(Style::default().fg(app.current_theme.fg()).bg(app.current_theme.bg()));
+    let inner_area = block.inner(area);
+    f.render_widget(block, area);
+
+    let faint = Style::default().fg(app.current_theme.palette[8]);
+    let unknown = || "-".to_string();
+    let brand = cpus.first().map(|c| c.brand().trim()).filter(|b| !b.is_empty()).unwrap_or("unknown");
+    let vendor = cpus.first().map(|c| c.vendor_id()).filter(|v| !v.is_empty()).unwrap_or("unknown");
+
+    let mut cores = format!(
+        "{} physical, {} logical",
+        app.sys.physical_core_count().map(|n| n.to_string()).unwrap_or_else(unknown),
+        cpus.len()
+    );
+    if let Some(sockets) = app.cpu_topology.sockets() {
+        cores.push_str(&format!(", {} socket{}", sockets, if sockets == 1 { "" } else { "s" }));
+    }
+    if let Some(nodes) = app.cpu_topology.numa_nodes() {
+        cores.push_str(&format!(", {} NUMA node{}", nodes, if nodes == 1 { "" } else { "s" }));
+    }
+
+    let current = app.cpu_freq_history.last().map(|(_, mhz)| *mhz).unwrap_or(0.0);
+    let min = app.cpu_freq_info.iter().filter_map(|i| i.min_mhz).min();
+    let max = app.cpu_freq_info.iter().filter_map(|i| i.max_mhz).max();
+    let mut governors: Vec<&str> = app.cpu_freq_info.iter().filter_map(|i| i.governor.as_deref()).collect();
+    governors.sort();
+    governors.dedup();
+    let mhz = |v: Option<u64>| v.map(|v| units::frequency(v as f64)).unwrap_or_else(unknown);
+
+    let field = |name: &str, value: String| Line::from(vec![Span::styled(format!("{:<11}", name), faint), Span::raw(value)]);
+    let info = vec![
+        field("Model", format!("{} ({})", brand, vendor)),
+        field("Cores", cores),
+        field("Frequency", format!("avg {}  min {}  max {}", units::frequency(current), mhz(min), mhz(max))),
+        field("Governor", if governors.is_empty() { unknown() } else { governors.join(", ") }),
+    ];
+
+    let show_chart = inner_area.height >= 14;
+    let chunks = Layout::default().direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(info.len() as u16),
+            Constraint::Length(if show_chart { 7 } else { 0 }),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .split(inner_area);
+    f.render_widget(Paragraph::new(info), chunks[0]);
+
+    if show_chart {
+        let observed = app.cpu_freq_history.iter().map(|(_, v)| *v).fold(0.0, f64::max);
+        let top = (max.unwrap_or(0) as f64).max(observed).max(1.0);
+        let datasets = vec![
+            Dataset::default()
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(app.current_theme.graph_colors()[0]))
+                .data(&app.cpu_freq_history),
+        ];
+        let chart = Chart::new(datasets)
+            .block(Block::default().borders(Borders::TOP | Borders::BOTTOM).border_style(faint)
+                .title(Span::styled("Average frequency", faint)))
+            .x_axis(Axis::default().bounds([app.tick_count - app.config.history_length as f64, app.tick_count]))
+            .y_axis(Axis::default().bounds([0.0, top]).style(faint)
+                .labels(vec![Span::raw("0"), Span::raw(units::frequency(top))]))
+            .legend_position(None);
+        f.render_widget(chart, chunks[1]);
+    }
+
+    let id = |ids: &[Option<usize>], i: usize| ids.get(i).copied().flatten().map(|v| v.to_string()).unwrap_or_else(unknown);
+    let rows: Vec<Row> = cpus.iter().enumerate().skip(app.cpu_details_scroll).map(|(i, cpu)| {
+        let freq = app.cpu_freq_info.get(i).cloned().unwrap_or_default();
+        Row::new(vec![
+            format!("CPU{}", i),
+            id(&app.cpu_topology.core, i),
+            id(&app.cpu_topology.socket, i),
+            id(&app.cpu_topology.numa, i),
+            units::frequency(cpu.frequency() as f64),
+            mhz(freq.min_mhz),
+            mhz(freq.max_mhz),
+            freq.governor.unwrap_or_else(unknown),
+            format!("{:.0}%", cpu.cpu_usage()),
+        ])
+    }).collect();
+    let header = Row::new(vec!["CPU", "Core", "Socket", "Node", "Current", "Min", "Max", "Governor", "Usage"])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let widths = [
+        Constraint::Length(7), Constraint::Length(5), Constraint::Length(7), Constraint::Length(5),
+        Constraint::Length(10), Constraint::Length(10), Constraint::Length(10), Constraint::Length(13), Constraint::Length(6),
+    ];
+    f.render_widget(Table::new(rows, widths).header(header), chunks[2]);
+
+    let hint = key_hints(app, &[(&[Action::SelectNext, Action::SelectPrevious], "Scroll"), (&[Action::Cancel], "Close")]);
+    f.render_widget(Paragraph::new(Span::styled(hint, faint)), chunks[3]);
+}
+
+/// Process count, CPU and memory per user over the filtered process list.
+fn render_user_summary(f: &mut Frame, app: &App, area: Rect) {
+    let units = &app.config.units;
+    let summaries = app.user_summaries();
+    let cpu_total: f32 = summaries.iter().map(|s| s.cpu).sum();
+    let total_memory = app.sys.total_memory().max(1);
+    let rows: Vec<Row> = summaries.iter().map(|s| {
+        Row::new(vec![
+            s.name.clone(),
+            s.processes.to_string(),
+            format!("{:.1}%", s.cpu),
+            format!("{:.0}%", if cpu_total > 0.0 { s.cpu / cpu_total * 100.0 } else { 0.0 }),
+            units.bytes(s.memory),
+            format!("{:.1}%", s.memory as f64 / total_memory as f64 * 100.0),
+        ])
+    }).collect();
+    let header = Row::new(vec!["User", "Procs", "CPU", "CPU share", "Memory", "Mem%"])
+        .style(Style::default().fg(app.current_theme.palette[4]).add_modifier(Modifier::BOLD))
+        .bottom_margin(1);
+    let widths = [
+        Constraint::Length(20), Constraint::Length(7), Constraint::Length(9),
+        Constraint::Length(10), Constraint::Length(12), Constraint::Length(7),
+    ];
+    f.render_widget(Table::new(rows, widths).header(header), area);
+}
+
+/// Everything known about one process, as a scrollable popup.
+fn render_process_details(f: &mut Frame, app: &mut App) {
+    let Some(details) = &app.process_details else { return };
+    let full = f.area();
+    let area = centered_rect(full.width.saturating_sub(8).max(40), full.height.saturating_sub(4).max(10), full);
+    f.render_widget(Clear, area);
+
+    let units = &app.config.units;
+    let faint = Style::default().fg(app.current_theme.palette[8]);
+    let heading = Style::default().fg(app.current_theme.palette[4]).add_modifier(Modifier::BOLD);
+    let field = |name: &str, value: String| Line::from(vec![Span::styled(format!("{:<14}", name), faint), Span::raw(value)]);
+    let section = |title: String| Line::from(Span::styled(title, heading));
+    let unknown = || "-".to_string();
+    let path = |p: Option<&std::path::Path>| p.map(|p| p.display().to_string()).unwrap_or_else(unknown);
+
+    let mut title = format!("Process {}", details.pid);
+    let mut lines = vec![];
+    match app.sys.process(details.pid) {
+        None => lines.push(Line::from(Span::styled("The process has exited.", faint))),
+        Some(p) => {
+            title = format!("Process {} ({})", details.pid, p.name().to_string_lossy());
+            let cmd: Vec<String> = p.cmd().iter().map(|a| a.to_string_lossy().into_owned()).collect();
+            let started = Local
+                .timestamp_opt(p.start_time() as i64, 0)
+                .single()
+                .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_else(unknown);
+            let id = |v: Option<String>| v.unwrap_or_else(unknown);
+
+            lines.push(section("Process".to_string()));
+            lines.push(field("Command", if cmd.is_empty() { unknown() } else { cmd.join(" ") }));
+            lines.push(field("Executable", path(p.exe())));
+            lines.push(field("Working dir", path(p.cwd())));
+            lines.push(field("Root", path(p.root())));
+            lines.push(field("Status", p.status().to_string()));
+            lines.push(field("Parent", id(p.parent().map(|pid| {
+                let name = app.sys.process(pid).map(|pp| pp.name().to_string_lossy().into_owned()).unwrap_or_default();
+                format!("{} {}", pid, name)
+            }))));
+            lines.push(field("Session", id(p.session_id().map(|s| s.to_string()))));
+            let user = |uid: Option<&sysinfo::Uid>| match uid {
+                Some(uid) => format!("{} ({})", user_name(&app.users, Some(uid)), **uid),
+                None => unknown(),
+            };
+            lines.push(field("Real user", user(p.user_id())));
+            lines.push(field("Effective user", user(p.effective_user_id())));
+            lines.push(field("Group", id(p.group_id().map(|g| g.to_string()))));
+            lines.push(field("Started", started));
+            lines.push(field("Run time", units::duration(p.run_time())));
+            lines.push(field("Threads", thread_count(p).map(|n| n.to_string()).unwrap_or_else(unknown)));
+
+            lines.push(Line::default());
+            lines.push(section("Resources".to_string()));
+            lines.push(field("CPU", format!("{:.1}%", p.cpu_usage())));
+            lines.push(field("Resident", units.bytes(p.memory())));
+            lines.push(field("Virtual", units.bytes(p.virtual_memory())));
+            let io = p.disk_usage();
+            lines.push(field("Disk read", format!(
+                "{} total, {}",
+                units.bytes(io.total_read_bytes),
+                units.rate(io.read_bytes as f64 / app.refresh_elapsed)
+            )));
+            lines.push(field("Disk written", format!(
+                "{} total, {}",
+                units.bytes(io.total_written_bytes),
+                units.rate(io.written_bytes as f64 / app.refresh_elapsed)
+            )));
+
+            let extras = &details.extras;
+            if let Some(cgroups) = &extras.cgroups {
+                lines.push(Line::default());
+                lines.push(section("Cgroups".to_string()));
+                lines.extend(cgroups.iter().map(|c| Line::raw(c.clone())));
+            }
+            if let Some(limits) = &extras.limits {
+                lines.push(Line::default());
+                lines.push(section("Limits".to_string()));
+                lines.push(Line::styled(format!("{:<26}{:<21}{:<21}{}", "Limit", "Soft", "Hard", "Units"), faint));
+                lines.extend(limits.iter().map(|[name, soft, hard, unit]| {
+                    Line::raw(format!("{:<26}{:<21}{:<21}{}", name, soft, hard, unit))
+                }));
+            }
+            if let Some(maps) = &extras.maps {
+                lines.push(Line::default());
+                lines.push(section(format!(
+                    "Memory maps ({} regions, {} mapped, {} files)",
+                    maps.regions,
+                    units.bytes(maps.mapped),
+                    maps.files.len()
+                )));
+                lines.extend(maps.files.iter().map(|file| Line::raw(file.clone())));
+            }
+            match &extras.open_files {
+                Some(files) => {
+                    lines.push(Line::default());
+                    lines.push(section(format!("Open files ({})", files.len())));
+                    lines.extend(files.iter().map(|(fd, target)| {
+                        Line::from(vec![Span::styled(format!("{:>5}  ", fd), faint), Span::raw(target.clone())])
+                    }));
+                }
+                None if cfg!(target_os = "linux") => {
+                    lines.push(Line::default());
+                    lines.push(section("Open files".to_string()));
+                    lines.push(Line::styled("permission denied", faint));
+                }
+                None => {}
+            }
+
+            let environ: Vec<String> = p.environ().iter().map(|e| e.to_string_lossy().into_owned()).collect();
+            lines.push(Line::default());
+            lines.push(section(format!("Environment ({})", environ.len())));
+            lines.extend(environ.into_iter().map(Line::raw));
+        }
+    }
+
+    let mut block = Block::default().title(title).borders(Borders::ALL)
+        .border_style(Style::default().fg(app.current_theme.border()))
+        .title_bottom(Line::styled(
+            key_hints(app, &[
+                (&[Action::SelectNext, Action::SelectPrevious, Action::PageDown, Action::PageUp], "Scroll"),
+                (&[Action::Signal], "Signal"),
+                (&[Action::Cancel], "Close"),
+            ]),
+            faint,
+        ))
+        .style(Style::default().fg(app.current_theme.fg()).bg(app.current_theme.bg()));
+    let inner_area = block.inner(area);
+    let history = app.process_history.as_ref().filter(|h| h.pid == details.pid);
+    let chart_height = if history.is_some() && inner_area.height >= 20 { 8 } else { 0 };
+    let chunks = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Length(chart_height), Constraint::Min(0)])
+        .split(inner_area);
+
+    let visible = chunks[1].height as usize;
+    let max_scroll = lines.len().saturating_sub(visible);
+    let scroll = details.scroll.min(max_scroll);
+    if lines.len() > visible {
+        block = block.title_bottom(Line::from(format!("{}-{}/{}", scroll + 1, scroll + visible, lines.len())).right_aligned());
+    }
+    f.render_widget(block, area);
+    if let Some(history) = history.filter(|_| chart_height > 0) {
+        render_process_history(f, app, history, chunks[0]);
+    }
+    f.render_widget(Paragraph::new(lines).scroll((scroll as u16, 0)), chunks[1]);
+    if let Some(details) = &mut app.process_details {
+        details.scroll = scroll;
+    }
+}
+
+/// Fixed keys shown in the help popup after the remappable ones.
+const HELP: &[(&str, &[(&str, &str)])] = &[
+    ("Filter mode", &[
+        ("Enter", "Keep the filter"),
+        ("Esc", "Clear the filter"),
+        ("Backspace", "Delete a character"),
+        ("Ctrl+R", "Toggle regex matching"),
+        ("Ctrl+S", "Toggle case-sensitive matching"),
+        ("Other bound keys", "Quit, help and navigation, if they do not type text"),
+    ]),
+    ("Signal dialog", &[
+        ("Enter / y", "Send the signal"),
+        ("n", "Cancel"),
+    ]),
+    ("Mouse", &[
+        ("Click row / header", "Select a process / sort by a column"),
+        ("Click panel", "Maximize / restore it"),
+        ("Click a header hint", "Run that action"),
+        ("Wheel", "Scroll the list or popup under the pointer"),
+    ]),
+];
+
+fn render_help(f: &mut Frame, app: &mut App) {
+    let full = f.area();
+    let faint = Style::default().fg(app.current_theme.palette[8]);
+    let heading = Style::default().fg(app.current_theme.palette[4]).add_modifier(Modifier::BOLD);
+    let key_style = Style::default().fg(app.current_theme.palette[6]);
+    let field = |name: &str, value: String| Line::from(vec![Span::styled(format!("{:<14}", name), faint), Span::raw(value)]);
+
+    let mode_str = match app.layout_mode {
+        LayoutMode::Dashboard => "Dashboard",
+        LayoutMode::Vertical => "Vertical",
+        LayoutMode::ProcessFocus => "Process Focus",
+    };
+    let mut lines = vec![
+        field("Theme", app.current_theme.name.clone()),
+        field("Layout", mode_str.to_string()),
+        field("Refresh", format!("{} ms", app.tick_rate.as_millis())),
+        field("Config", app.config_path.as_ref().map(|p| p.display().to_string()).unwrap_or_else(|| "-".to_string())),
+        field("Keys", format!("{} preset", app.keymap.preset.name())),
+    ];
+    for (context, title) in [(Context::Global, "Global"), (Context::Processes, "Process table")] {
+        lines.push(Line::default());
+        lines.push(Line::styled(title, heading));
+        lines.extend(Action::ALL.iter().filter(|a| a.context() == context).map(|action| {
+            let keys: Vec<String> = app.keymap.keys(*action).iter().map(|k| k.to_string()).collect();
+            let keys = if keys.is_empty() {
+                Span::styled(format!("{:<21} ", "-"), faint)
+            } else {
+                Span::styled(format!("{:<21} ", keys.join(" / ")), key_style)
+            };
+            Line::from(vec![keys, Span::raw(action.description())])
+        }));
+    }
+    for (context, bindings) in HELP {
+        lines.push(Line::default());
+        lines.push(Line::styled(*context, heading));
+        lines.extend(bindings.iter().map(|(keys, action)| {
+            Line::from(vec![Span::styled(format!("{:<22}", keys), key_style), Span::raw(*action)])
+        }));
+    }
+
+    let area = centered_rect(72, lines.len() as u16 + 2, full);
+    f.render_widget(Clear, area);
+    let mut block = Block::default().title("Help").borders(Borders::ALL)
+        .border_style(Style::default().fg(app.current_theme.border()))
+        .title_bottom(Line::styled(key_hints(app, &[(&[Action::SelectNext, Action::SelectPrevious], "Scroll"), (&[Action::Cancel], "Close")]), faint))
+        .style(Style::default().fg(app.current_theme.fg()).bg(app.current_theme.bg()));
+    let inner_area = block.inner(area);
+
+    let visible = inner_area.height as usize;
+    let scroll = app.help_scroll.min(lines.len().saturating_sub(visible));
+    if lines.len() > visible {
+        block = block.title_bottom(Line::from(format!("{}-{}/{}", scroll + 1, scroll + visible, lines.len())).right_aligned());
+    }
+    f.render_widget(block, area);
+    f.render_widget(Paragraph::new(lines).scroll((scroll as u16, 0)), inner_area);
+    app.help_scroll = scroll;
+}
+
+/// CPU% and resident memory charts of the process being watched.
+fn render_process_history(f: &mut Frame, app: &App, history: &ProcessHistory, area: Rect) {
+    let units = &app.config.units;
+    let faint = Style::default().fg(app.current_theme.palette[8]);
+    let graph_colors = app.current_theme.graph_colors();
+    let x_bounds = [app.tick_count - app.config.history_length as f64, app.tick_count];
+    let chunks = Layout::default().direction(Direction::Horizontal)
+        .constraints([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
+        .split(area);
+
+    let last = |data: &[(f64, f64)]| data.last().map(|(_, v)| *v).unwrap_or(0.0);
+    let peak = |data: &[(f64, f64)]| data.iter().map(|(_, v)| *v).fold(0.0, f64::max);
+
+    // Multi-threaded processes can exceed 100%
+    let cpu_top = peak(&history.cpu).max(100.0);
+    let memory_top = peak(&history.memory).max(1024.0) * 1.1;
+    let charts = [
+        (
+            &history.cpu,
+            format!("CPU {:.1}%  peak {:.1}%", last(&history.cpu), peak(&history.cpu)),
+            cpu_top,
+            format!("{:.0}%", cpu_top),
+            graph_colors[0],
+        ),
+        (
+            &history.memory,
+            format!("RSS {}  peak {}", units.bytes(last(&history.memory) as u64), units.bytes(peak(&history.memory) as u64)),
+            memory_top,
+            units.bytes(memory_top as u64),
+            graph_colors[1 % graph_colors.len()],
+        ),
+    ];
+    for ((data, title, top, top_label, color), chunk) in charts.into_iter().zip(chunks.iter()) {
+        let datasets = vec![
+            Dataset::default()
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(color))
+                .data(data),
+        ];
+        let chart = Chart::new(datasets)
+            .block(Block::default().borders(Borders::BOTTOM).border_style(faint).title(Span::styled(title, faint)))
+            .x_axis(Axis::default().bounds(x_bounds))
+            .y_axis(Axis::default().bounds([0.0, top]).style(faint)
+                .labels([Span::styled("0", faint), Span::styled(top_label, faint)]))
+            .legend_position(None);
+        f.render_widget(chart, *chunk);
+    }
+}
+
+fn render_signal_dialog(f: &mut Frame, app: &App) {
+    let Some(dialog) = &app.signal_dialog else { return };
+    let area = centered_rect(44, SIGNALS.len() as u16 + 4, f.area());
+    f.render_widget(Clear, area);
+
+    let title = format!("Signal {} ({})", dialog.pid, dialog.name);
+    let block = Block::default().title(title).borders(Borders::ALL)
+        .border_style(Style::default().fg(app.current_theme.palette[1]))
+        .style(Style::default().fg(app.current_theme.fg()).bg(app.current_theme.bg()));
+    let inner_area = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default().direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(inner_area);
+
+    let items: Vec<ListItem> = SIGNALS.iter().map(|(_, name)| ListItem::new(*name)).collect();
+    let list = List::new(items)
+        .highlight_style(highlight_style(app))
+        .highlight_symbol("> ");
+    let mut state = ListState::default().with_selected(Some(dialog.selected));
+    f.render_stateful_widget(list, chunks[0], &mut state);
+
+    let hint = if dialog.confirming {
+        Line::from(Span::styled(
+            format!("Send {}? [y/Enter] Yes [{}] No", SIGNALS[dialog.selected].1, bound_keys(app, &[Action::Cancel], "/").map_or("n".to_string(), |k| format!("n/{}", k))),
+            Style::default().fg(app.current_theme.palette[1]).add_modifier(Modifier::BOLD),
+        ))
+    } else {
+        let hint = format!("[Enter] Select {}", key_hints(app, &[(&[Action::Cancel], "Cancel")]));
+        Line::from(Span::styled(hint, Style::default().fg(app.current_theme.palette[8])))
+    };
+    f.render_widget(Paragraph::new(hint), chunks[1]);
 }
@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+
+/// Prefixes used when scaling byte counts.
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnitSystem {
+    /// Powers of 1024: KiB, MiB, GiB...
+    #[default]
+    Iec,
+    /// Powers of 1000: kB, MB, GB...
+    Si,
+}
+
+/// How sizes and rates are shown, read from the `[units]` config section.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Units {
+    pub system: UnitSystem,
+    /// Show network rates in bits per second instead of bytes.
+    pub network_bits: bool,
+}
+
+const IEC_PREFIXES: [&str; 7] = ["", "Ki", "Mi", "Gi", "Ti", "Pi", "Ei"];
+const SI_PREFIXES: [&str; 7] = ["", "k", "M", "G", "T", "P", "E"];
+
+impl Units {
+    /// Scales `value` to the largest prefix that keeps it at or above one,
+    /// with about three significant digits. The prefix is picked on the
+    /// rounded value, so 1023.6 KiB reads `1.00 MiB` rather than `1024 KiB`.
+    fn scale(&self, value: f64, unit: &str) -> String {
+        let (base, prefixes) = match self.system {
+            UnitSystem::Iec => (1024.0, IEC_PREFIXES),
+            UnitSystem::Si => (1000.0, SI_PREFIXES),
+        };
+        let mut value = value.max(0.0);
+        let mut prefix = 0;
+        loop {
+            let (shown, decimals) = round_significant(value, prefix > 0);
+            if shown >= base && prefix < prefixes.len() - 1 {
+                value /= base;
+                prefix += 1;
+            } else {
+                return format!("{:.*} {}{}", decimals, shown, prefixes[prefix], unit);
+            }
+        }
+    }
+
+    pub fn bytes(&self, bytes: u64) -> String {
+        self.scale(bytes as f64, "B")
+    }
+
+    pub fn rate(&self, bytes_per_sec: f64) -> String {
+        self.scale(bytes_per_sec, "B/s")
+    }
+
+    /// A network rate, in bits per second when `network_bits` is set.
+    pub fn network_rate(&self, bytes_per_sec: f64) -> String {
+        if self.network_bits {
+            self.scale(bytes_per_sec * 8.0, "bit/s")
+        } else {
+            self.rate(bytes_per_sec)
+        }
+    }
+}
+
+/// Rounds to about three significant digits, or to a whole number unless
+/// `fraction` is set. The decimals follow the rounded value, so 99.96 gives
+/// `100` rather than `100.0`.
+fn round_significant(value: f64, fraction: bool) -> (f64, usize) {
+    let mut decimals: usize = match value {
+        _ if !fraction => 0,
+        v if v < 10.0 => 2,
+        v if v < 100.0 => 1,
+        _ => 0,
+    };
+    loop {
+        let factor = 10f64.powi(decimals as i32);
+        let rounded = (value * factor).round() / factor;
+        if decimals == 0 || rounded < 10f64.powi(3 - decimals as i32) {
+            return (rounded, decimals);
+        }
+        decimals -= 1;
+    }
+}
+
+/// Compact duration with the two most significant units, e.g. `3h 04m`.
+pub fn duration(secs: u64) -> String {
+    let (days, hours, minutes, seconds) = (secs / 86400, (secs % 86400) / 3600, (secs % 3600) / 60, secs % 60);
+    if days > 0 {
+        format!("{}d {:02}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {:02}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {:02}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+pub fn frequency(mhz: f64) -> String {
+    if mhz >= 1000.0 {
+        format!("{:.2} GHz", mhz / 1000.0)
+    } else {
+        format!("{:.0} MHz", mhz)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IEC: Units = Units { system: UnitSystem::Iec, network_bits: false };
+    const SI: Units = Units { system: UnitSystem::Si, network_bits: false };
+
+    #[test]
+    fn iec_boundaries() {
+        assert_eq!(IEC.bytes(0), "0 B");
+        assert_eq!(IEC.bytes(1023), "1023 B");
+        assert_eq!(IEC.bytes(1024), "1.00 KiB");
+        assert_eq!(IEC.bytes(10 * 1024 - 3), "10.0 KiB");
+        assert_eq!(IEC.bytes(100 * 1024 - 30), "100 KiB");
+        assert_eq!(IEC.bytes(1024 * 1024 - 400), "1.00 MiB");
+        assert_eq!(IEC.rate(1023.6), "1.00 KiB/s");
+    }
+
+    #[test]
+    fn si_boundaries() {
+        assert_eq!(SI.bytes(999), "999 B");
+        assert_eq!(SI.bytes(1000), "1.00 kB");
+        assert_eq!(SI.bytes(9_996), "10.0 kB");
+        assert_eq!(SI.bytes(99_960), "100 kB");
+        assert_eq!(SI.bytes(999_600), "1.00 MB");
+        assert_eq!(SI.bytes(1_500_000_000), "1.50 GB");
+    }
+
+    #[test]
+    fn network_rate_in_bits() {
+        let bits = Units { system: UnitSystem::Si, network_bits: true };
+        assert_eq!(bits.network_rate(125.0), "1.00 kbit/s");
+        assert_eq!(bits.network_rate(100.0), "800 bit/s");
+        assert_eq!(SI.network_rate(125.0), "125 B/s");
+    }
+
+    #[test]
+    fn duration_keeps_two_units() {
+        assert_eq!(duration(59), "59s");
+        assert_eq!(duration(60), "1m 00s");
+        assert_eq!(duration(3599), "59m 59s");
+        assert_eq!(duration(3600), "1h 00m");
+        assert_eq!(duration(86_399), "23h 59m");
+        assert_eq!(duration(86_400 + 5 * 3600), "1d 05h");
+    }
+}